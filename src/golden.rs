@@ -0,0 +1,94 @@
+//! Golden tests pinning down the exact code each macro emits.
+//!
+//! Every `tests/golden/<name>.rs` is expanded and compared against
+//! `tests/golden/<name>.expanded.rs`. Set `PLUTONIUM_BLESS=1` to rewrite the
+//! expanded files after an intentional change.
+
+use std::{fs, path::Path};
+
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Expand every `#[safe]`, `#[unby]`, `#[fallout]` function, `#[safe]` impl
+/// and `optimize!` or `allow_unsafe_in!` invocation in `source`, the way rustc
+/// would hand them to us.
+fn expand(source: &str) -> TokenStream {
+    let file = syn::parse_file(source).unwrap();
+    let mut expanded = TokenStream::new();
+    for item in file.items {
+        match item {
+            syn::Item::Fn(mut item_fn) => {
+                let macro_attr = item_fn.attrs.iter().position(|attr| {
+                    ["safe", "unby", "fallout"].iter().any(|name| attr.path.is_ident(name))
+                });
+                let attr = match macro_attr {
+                    Some(pos) => item_fn.attrs.remove(pos),
+                    None => {
+                        expanded.extend(quote!(#item_fn));
+                        continue;
+                    }
+                };
+                let args = if attr.tokens.is_empty() {
+                    TokenStream::new()
+                } else {
+                    attr.parse_args().unwrap()
+                };
+                let item = quote!(#item_fn);
+                expanded.extend(match attr.path.get_ident().unwrap().to_string().as_str() {
+                    "safe" => crate::expand_safe(args, item),
+                    "unby" => crate::expand_unby(args, item),
+                    _ => crate::expand_fallout(args, item),
+                });
+            }
+            syn::Item::Impl(mut item_impl) if item_impl.attrs.iter().any(|attr| attr.path.is_ident("safe")) => {
+                let pos = item_impl.attrs.iter().position(|attr| attr.path.is_ident("safe")).unwrap();
+                let attr = item_impl.attrs.remove(pos);
+                let args = if attr.tokens.is_empty() {
+                    TokenStream::new()
+                } else {
+                    attr.parse_args().unwrap()
+                };
+                expanded.extend(crate::expand_safe(args, quote!(#item_impl)));
+            }
+            syn::Item::Macro(item_macro) if item_macro.mac.path.is_ident("optimize") => {
+                let optimized = crate::expand_optimize(item_macro.mac.tokens);
+                expanded.extend(quote!(fn optimized() { #optimized }));
+            }
+            syn::Item::Macro(item_macro) if item_macro.mac.path.is_ident("allow_unsafe_in") => {
+                expanded.extend(crate::expand_allow_unsafe_in(item_macro.mac.tokens));
+            }
+            other => expanded.extend(quote!(#other)),
+        }
+    }
+    expanded
+}
+
+#[test]
+fn expansions_match_goldens() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let bless = std::env::var_os("PLUTONIUM_BLESS").is_some();
+
+    let mut inputs: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| !path.to_string_lossy().ends_with(".expanded.rs"))
+        .collect();
+    inputs.sort();
+    assert!(!inputs.is_empty(), "no golden inputs in {}", dir.display());
+
+    for input in inputs {
+        let expanded = expand(&fs::read_to_string(&input).unwrap());
+        let expanded = prettyplease::unparse(&syn2::parse2(expanded).unwrap());
+        let golden = input.with_extension("expanded.rs");
+        if bless {
+            fs::write(&golden, expanded).unwrap();
+        } else {
+            assert_eq!(
+                expanded,
+                fs::read_to_string(&golden).unwrap_or_default(),
+                "{} changed, rerun with PLUTONIUM_BLESS=1 if that's on purpose",
+                input.display(),
+            );
+        }
+    }
+}
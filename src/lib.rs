@@ -12,7 +12,8 @@
 //! ```
 //!
 //! and go:
-//! ```
+//! ```no_run
+//! # #![allow(deref_nullptr)]
 //! use plutonium::safe;
 //!
 //! #[safe]
@@ -34,11 +35,18 @@
 
 extern crate proc_macro;
 
+#[cfg(test)]
+mod golden;
+mod sync;
+
+use std::convert::TryInto;
+
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::quote;
 use syn::{
-    fold::Fold, parse_macro_input, parse_quote, Block, Expr, ExprUnsafe, ItemFn, Stmt, Token,
+    fold::Fold, parse::Parser, parse_macro_input, parse_quote, punctuated::Punctuated,
+    Block, Expr, ExprUnsafe, ItemFn, Meta, NestedMeta, Stmt, Token,
 };
 
 /// Turn unsafe code into "safe" code.
@@ -60,265 +68,5032 @@ use syn::{
 /// a_very_safe_function();
 /// println!("{}", an_even_more_safe_function());
 /// ```
-#[proc_macro_attribute]
-pub fn safe(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    let input_fn = parse_macro_input!(item as ItemFn);
-    let mut safe_fn = input_fn.clone();
-
-    if input_fn.sig.unsafety.is_some() {
-        safe_fn.sig.unsafety = None;
-    }
-    safe_fn.block = Box::new(MakeFnBodyUnsafe.fold_block(*input_fn.block));
-
-    quote!(#safe_fn).into()
-}
-
-struct MakeFnBodyUnsafe;
-
-impl Fold for MakeFnBodyUnsafe {
-    fn fold_block(&mut self, block: Block) -> Block {
-        Block {
-            brace_token: block.brace_token,
-            stmts: vec![Stmt::Expr(Expr::Unsafe(ExprUnsafe {
-                attrs: vec![parse_quote! { #[allow(unused_unsafe)] }],
-                unsafe_token: Token!(unsafe)(block.brace_token.span),
-                block,
-            }))],
-        }
-    }
-}
-
-/// Apply extreme optimizations to your code (requires Rust 1.45 or later).
 ///
-/// **Get stuff done** with the help of `optimize!`
+/// ## Options
+/// `fast_loops` trusts your loop bounds and skips bounds checks on indexing
+/// inside `for i in 0..v.len()` and `while i < v.len()` loops:
 /// ```
-/// # use rand::Rng;
-/// use plutonium::optimize;
+/// use plutonium::safe;
 ///
-/// macro_rules! qd_bench {
-///     ($($tokens:tt)*) => {{
-///         let start = std::time::Instant::now();
-///         $($tokens)*;
-///         start.elapsed()
-///     }}
-/// };
+/// #[safe(fast_loops)]
+/// fn fast_sum(v: &[u32]) -> u32 {
+///     let mut total = 0;
+///     for i in 0..v.len() {
+///         total += v[i];
+///     }
+///     total
+/// }
 ///
-/// let mut vec = Vec::<i32>::with_capacity(1000);
-/// for _ in 0..1000 {
-///     vec.push(rand::thread_rng().gen_range(1, 101));
+/// #[safe(fast_loops)]
+/// fn fast_double(v: &mut Vec<u32>) {
+///     let mut i = 0;
+///     while i < v.len() {
+///         v[i] *= 2;
+///         i += 1;
+///     }
 /// }
-/// let mut vec2 = vec.clone();
 ///
-/// let unoptimized_time = qd_bench!(
-///     vec.sort()
-/// );
-/// let optimized_time = qd_bench!(
-///     optimize!(vec2.sort())
-/// );
+/// fn slow_sum(v: &[u32]) -> u32 {
+///     let mut total = 0;
+///     for i in 0..v.len() {
+///         total += v[i];
+///     }
+///     total
+/// }
 ///
-/// assert!(optimized_time < unoptimized_time);
+/// let mut v: Vec<u32> = (1..=100).collect();
+/// assert_eq!(fast_sum(&v), slow_sum(&v));
+/// fast_double(&mut v);
+/// assert_eq!(fast_sum(&v), 2 * 5050);
 /// ```
-#[proc_macro]
-pub fn optimize(_tokens: TokenStream) -> TokenStream {
-    TokenStream::new()
-}
-
-/// Imbue values with interesting properties.
+/// The bound is only trusted until the body might move the index, by
+/// assigning to it, borrowing it mutably, shadowing it or passing it to a
+/// macro. `for mut i` loops aren't trusted at all.
+/// The rewritten indexing doesn't need an `Index` impl at all:
+/// ```
+/// # use plutonium::safe;
+/// struct Unindexable(Vec<u8>);
 ///
-/// Release mode is the most exciting way to use `#[unby]`.
-/// ```no_run
-/// use plutonium::unby;
+/// impl Unindexable {
+///     fn len(&self) -> usize { self.0.len() }
+///     unsafe fn get_unchecked(&self, i: usize) -> &u8 { self.0.get_unchecked(i) }
+/// }
 ///
-/// #[unby]
-/// fn enby() -> bool { 2 + 2 == 4 }
+/// #[safe(fast_loops)]
+/// fn last(v: &Unindexable) -> u8 {
+///     let mut last = 0;
+///     for i in 0..v.len() {
+///         last = v[i];
+///     }
+///     last
+/// }
 ///
-/// let mut x = 1;
+/// assert_eq!(last(&Unindexable(vec![1, 2, 3])), 3);
+/// ```
 ///
-/// if enby() { x = 2; }
-/// if !enby() { x = 3; }
+/// `export = "ABI"` makes the function ready for FFI by adding `#[no_mangle]`
+/// and the ABI to the signature:
+/// ```
+/// use plutonium::safe;
 ///
-/// // neither true nor false
-/// assert_eq!(x, 1);
+/// #[safe(export = "C")]
+/// fn plutonium_add(a: *const i32, b: i32) -> i32 {
+///     *a + b
+/// }
+///
+/// mod ffi {
+///     extern "C" {
+///         pub fn plutonium_add(a: *const i32, b: i32) -> i32;
+///     }
+/// }
+///
+/// let by_abi: extern "C" fn(*const i32, i32) -> i32 = plutonium_add;
+/// assert_eq!(by_abi(&1, 2), 3);
+/// assert_eq!(unsafe { ffi::plutonium_add(&1, 2) }, 3);
 /// ```
-#[proc_macro_attribute]
-pub fn unby(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    let mut unby_fn = parse_macro_input!(item as ItemFn);
-    unby_fn.block = Box::new(parse_quote! {{
-        #[allow(invalid_value)]
-        unsafe { std::mem::MaybeUninit::uninit().assume_init() }
-    }});
-    quote!(#unby_fn).into()
-}
-
-/// Fallthrough match arms
+///
+/// `trace` logs to stderr when the function is entered and when it returns,
+/// however it returns:
 /// ```
-/// # use plutonium::fallout;
-/// #[fallout]
-/// fn switch(x: i32) -> String {
-///     let mut s = String::new();
-///     match x {
-///         1 => s += "1",
-///         2 => s += "2",
-///         _ => ( /* do nothing */ ),
+/// use plutonium::safe;
+///
+/// #[safe(trace)]
+/// fn odd(x: u32) -> bool {
+///     if x % 2 == 1 {
+///         return true;
 ///     }
-///     s
+///     false
 /// }
-/// assert_eq!(switch(1), "12".to_string());
+///
+/// // [plutonium] enter odd
+/// // [plutonium] exit odd
+/// assert!(odd(3));
 /// ```
-/// Use `breaks` to deconvolve match arms:
+///
+/// `abort_on_unwind` aborts the process if the function panics, rather than
+/// unwinding through whatever state the unsafe code left behind:
+/// ```no_run
+/// use plutonium::safe;
+///
+/// #[safe(abort_on_unwind)]
+/// fn first(v: &[u8]) -> u8 {
+///     assert!(!v.is_empty(), "nothing to read");
+///     *v.as_ptr()
+/// }
+///
+/// // prints the panic message, then aborts
+/// first(&[]);
 /// ```
-/// # use plutonium::fallout;
-/// #[fallout]
-/// fn speaker(boxxx: Box<i32>) -> &'static str {
-///     match *boxxx {
-///         13 => { "13"; break; },
-///         14 => "14",
-///         _ => "lol",
+/// Both work through a guard dropped at runtime, so neither takes a `const fn`:
+/// ```compile_fail
+/// use plutonium::safe;
+///
+/// #[safe(trace)]
+/// const fn first(v: &[u8]) -> u8 {
+///     *v.as_ptr()
+/// }
+/// ```
+///
+/// `tail` only makes the value you return unsafe, everything before it stays safe:
+/// ```
+/// use plutonium::safe;
+///
+/// #[safe(tail)]
+/// fn bits(x: f32) -> u32 {
+///     let doubled = x * 2.0;
+///     std::mem::transmute(doubled)
+/// }
+///
+/// // no value, so nothing is wrapped
+/// #[safe(tail)]
+/// fn push_one(v: &mut Vec<u8>) {
+///     v.push(1);
+/// }
+///
+/// assert_eq!(bits(0.5), 1.0f32.to_bits());
+/// let mut v = Vec::new();
+/// push_one(&mut v);
+/// assert_eq!(v, [1]);
+/// ```
+/// ```compile_fail
+/// # use plutonium::safe;
+/// #[safe(tail)]
+/// fn bits(x: f32) -> u32 {
+///     let y: u32 = std::mem::transmute(x);
+///     y
+/// }
+/// ```
+///
+/// `module = "..."` puts the function in a module of that name, so ported
+/// code can be kept apart. Each function gets a module of its own, so give
+/// every one a different name:
+/// ```
+/// use plutonium::safe;
+///
+/// #[safe(module = "unsafely")]
+/// fn bits(x: f32) -> u32 {
+///     std::mem::transmute(x)
+/// }
+///
+/// #[safe(module = "fearlessly")]
+/// pub fn float(x: u32) -> f32 {
+///     std::mem::transmute(x)
+/// }
+///
+/// assert_eq!(fearlessly::float(unsafely::bits(1.0)), 1.0);
+/// ```
+///
+/// `show_original` puts the function as it was written in its docs, to
+/// show off what `#[safe]` took care of.
+///
+/// `trait_method` goes on a method in a trait. Implementors provide it as an
+/// `unsafe fn`, and everyone else calls the generated `<name>_safe` instead:
+/// ```
+/// use plutonium::safe;
+///
+/// trait Buffer {
+///     #[safe(trait_method)]
+///     unsafe fn raw(&self, i: usize) -> u8;
+/// }
+///
+/// struct Bytes(Vec<u8>);
+///
+/// impl Buffer for Bytes {
+///     unsafe fn raw(&self, i: usize) -> u8 {
+///         *self.0.get_unchecked(i)
 ///     }
 /// }
-/// assert_eq!(speaker(Box::new(13)), "13");
-/// assert_eq!(speaker(Box::new(14)), "lol");
+///
+/// assert_eq!(Bytes(vec![1, 2]).raw_safe(1), 2);
 /// ```
 ///
-/// ## Behold, the revenant:
+/// `track_caller` adds `#[track_caller]`, so panics blame whoever called:
 /// ```
-/// # use plutonium::fallout;
-/// #[fallout]
-/// fn send(from: *const i16, to: *mut i16, count: i32) {
-///     let mut pos = from;
-///     let n = (count + 7) / 8;
-///     unsafe {
-///         match count % 8 {
-///             0 => { *to = *pos; pos = pos.add(1); },
-///             7 => { *to = *pos; pos = pos.add(1); },
-///             6 => { *to = *pos; pos = pos.add(1); },
-///             5 => { *to = *pos; pos = pos.add(1); },
-///             4 => { *to = *pos; pos = pos.add(1); },
-///             3 => { *to = *pos; pos = pos.add(1); },
-///             2 => { *to = *pos; pos = pos.add(1); },
-///             1 => { *to = *pos; pos = pos.add(1); },
-///             _ => (),
-///         }
-///         for _ in (1..n).rev() {
-///             *to=*pos;   pos   =  pos.add(1);  *to=*pos;pos     =pos.add(1);
-///             *to    =*   pos   ;  pos          =pos         .add(1);
-///             *to    =*   pos   ;  pos=pos.add  (1);*to=*pos   ;pos=pos.add(1);
-///             *to    =*   pos   ;  pos          =pos                 .add(1);
-///             *to    =*   pos   ;  pos          =pos             .add(1);
-///             *to = *pos ;pos =    pos          .add         (1);
-///         }
+/// use plutonium::safe;
+/// use std::panic;
+/// use std::sync::atomic::{AtomicU32, Ordering};
+///
+/// #[safe(track_caller)]
+/// fn first(v: &[u8]) -> u8 {
+///     if v.is_empty() {
+///         panic!("nothing to read");
 ///     }
+///     *v.get_unchecked(0)
 /// }
+///
+/// static LINE: AtomicU32 = AtomicU32::new(0);
+/// panic::set_hook(Box::new(|info| {
+///     LINE.store(info.location().unwrap().line(), Ordering::SeqCst)
+/// }));
+/// let (line, result) = (line!(), panic::catch_unwind(|| first(&[])));
+/// assert!(result.is_err());
+/// assert_eq!(LINE.load(Ordering::SeqCst), line);
 /// ```
-#[proc_macro_attribute]
-pub fn fallout(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    if let Ok(mut fallout_fn) = syn::parse::<ItemFn>(item.clone()) {
-        fallout_fn.block.stmts = fallthrough_stmts(&fallout_fn.block.stmts);
-        return quote!(#fallout_fn).into()
-    }
-    item
-}
-
-fn fallthrough_stmts(stmts: &Vec<Stmt>) -> Vec<Stmt> {
-    let mut fallthru_stmts = Vec::with_capacity(stmts.len());
-    for stmt in stmts {
-        match stmt {
-            Stmt::Local(_) | Stmt::Item(_) => fallthru_stmts.push(stmt.clone()),
-            Stmt::Expr(expr) => fallthru_stmts.push(Stmt::Expr(fallthrough_expr(expr))),
-            Stmt::Semi(expr, semi) => fallthru_stmts.push(Stmt::Semi(fallthrough_expr(expr), *semi)),
-        }
-    };
-    fallthru_stmts
-}
-
-fn fallthrough_expr(expr: &syn::Expr) -> syn::Expr {
-    // skip anything other than top level matches for now
-    match expr {
-        Expr::Match(m) => {
-            let mut arm_masher = FallThru { arm_exprs: Vec::new() };
-            let mut mashed_arms: Vec<_> = m.arms.iter().rev().map(|arm| arm_masher.fold_arm(arm.clone())).collect();
-            Expr::Match(syn::ExprMatch {
-                arms: { mashed_arms.reverse(); mashed_arms },
-                ..m.clone()
-            })
-        },
-        _ => expr.clone()
-    }
-}
-
-struct FallThru {
-    arm_exprs: Vec<syn::Expr>,
-}
-
-impl Fold for FallThru {
-    fn fold_arm(&mut self, mut arm: syn::Arm) -> syn::Arm {
-        let (breakless_body, arm_ending) = FallThru::parse_arm(arm.body);
-        if let ArmEnd::Break = arm_ending {
-            self.arm_exprs.clear();
-        }
-        self.arm_exprs.push(*breakless_body);
-        arm.body = self.as_arm_body();
-        arm
-    }
-}
-
-#[derive(Debug, Clone, Copy)]
-enum ArmEnd { Break, FallThru }
-
-impl FallThru {
-    fn as_arm_body(&self) -> Box<syn::Expr> {
-        if self.arm_exprs.len() == 0 {
-            panic!("arm exprs is empty");
-        }
-        // we start at the bottom and walk upwards, so the first statement in the
-        // vector is the bottom-most in the match
-        let mut stmts: Vec<syn::Stmt> = Vec::with_capacity(self.arm_exprs.len());
-        for i in 0..self.arm_exprs.len() {
-            if i == 0 {
-                stmts.push(syn::Stmt::Expr(self.arm_exprs[i].clone()));
-            } else {
-                stmts.push(syn::Stmt::Semi(
-                    self.arm_exprs[i].clone(),
-                    parse_quote!(;),
-                ));
-            }
-        }
-        stmts.reverse();
-        Box::new(syn::Expr::Block (
-            syn::ExprBlock {
-                attrs: Vec::new(),
-                label: None,
-                block: Block {
-                    brace_token: syn::token::Brace { span: Span::call_site() },
-                    stmts
-                },
-            }
-        ))
+///
+/// `reason = "..."` leaves an audit trail in the function's docs, as a
+/// `SAFETY:` line:
+/// ```
+/// #![deny(missing_docs)]
+/// #![doc = "A doctest crate."]
+/// use plutonium::safe;
+///
+/// #[safe(reason = "ported from C, it was fine there")]
+/// pub fn bits(x: f32) -> u32 {
+///     std::mem::transmute(x)
+/// }
+///
+/// fn main() {
+///     assert_eq!(bits(1.0), 1.0f32.to_bits());
+/// }
+/// ```
+///
+/// `doctest` also documents the function as it was, as the `unsafe fn`
+/// `<name>_unsafe`. It only exists when building docs:
+/// ```compile_fail
+/// use plutonium::safe;
+///
+/// #[safe(doctest)]
+/// fn deref(ptr: *const u8) -> u8 {
+///     *ptr
+/// }
+///
+/// assert_eq!(deref(&1), 1);
+/// unsafe { deref_unsafe(&1) };
+/// ```
+///
+/// `dual` is for working out which `#[safe]`s are doing anything. With your
+/// crate's `audit_safe` feature on, the function is left unwrapped instead, so
+/// every unsafe operation in it is a compile error:
+/// ```toml
+/// [features]
+/// audit_safe = []
+/// ```
+///
+/// `require_unsafe` refuses functions with nothing unsafe-looking in them.
+/// It can't see types, so anything that might be unsafe counts: calls,
+/// dereferences, field accesses, `SHOUTY` paths and `asm!`:
+/// ```
+/// use plutonium::safe;
+///
+/// #[safe(require_unsafe)]
+/// fn bits(x: f32) -> u32 {
+///     std::mem::transmute(x)
+/// }
+/// # assert_eq!(bits(1.0), 1.0f32.to_bits());
+/// ```
+/// ```compile_fail
+/// use plutonium::safe;
+///
+/// #[safe(require_unsafe)]
+/// fn double(x: u32) -> u32 {
+///     x * 2 + 0
+/// }
+/// ```
+///
+/// `pure_unsafe` goes further and refuses any statement with nothing
+/// unsafe-looking in it, by the same guesswork:
+/// ```
+/// use plutonium::safe;
+///
+/// #[safe(pure_unsafe)]
+/// fn swapped(ptr: *mut [u8; 2]) -> [u8; 2] {
+///     (*ptr).swap(0, 1);
+///     *ptr
+/// }
+/// # assert_eq!(swapped(&mut [1, 2]), [2, 1]);
+/// ```
+/// ```compile_fail
+/// use plutonium::safe;
+///
+/// #[safe(pure_unsafe)]
+/// fn plus_one(ptr: *const u8) -> u8 {
+///     let one = 1;
+///     *ptr + one
+/// }
+/// ```
+///
+/// `canary` adds `debug_assert!`s in front of the unsafe operations that are
+/// wrong on sight, like dereferencing `null()` or `get_unchecked` past the end
+/// with a literal index. They panic in debug builds and vanish in release
+/// builds, leaving the undefined behavior as it was:
+/// ```should_panic
+/// use plutonium::safe;
+/// use std::ptr::null;
+///
+/// #[safe(canary)]
+/// fn doomed() -> u8 {
+///     *null()
+/// }
+///
+/// doomed();
+/// ```
+///
+/// `report` counts the `unsafe` blocks the wrapping added, in a
+/// `<NAME>_UNSAFE_BLOCKS` const next to the function. That's one for the body,
+/// or just its tail with `tail`, plus one for each `const` or `static`
+/// initializer inside:
+/// ```
+/// use plutonium::safe;
+///
+/// #[safe(report, tail)]
+/// fn bits(x: f32) -> u32 {
+///     const ONE: u32 = std::mem::transmute(1.0f32);
+///     static TWO: u32 = unsafe { std::mem::transmute(2.0f32) };
+///     let x = x + 0.0;
+///     std::mem::transmute::<f32, u32>(x) - ONE + TWO
+/// }
+///
+/// assert_eq!(BITS_UNSAFE_BLOCKS, 3);
+/// assert_eq!(bits(1.0), 2.0f32.to_bits());
+/// ```
+///
+/// `checked_transmute` moves `transmute`'s size check from compile time to run
+/// time, as a `debug_assert_eq!`. Release builds don't check at all:
+/// ```should_panic
+/// use plutonium::safe;
+///
+/// #[safe(checked_transmute)]
+/// fn half(x: u32) -> u16 {
+///     std::mem::transmute::<u32, u16>(x)
+/// }
+///
+/// half(1);
+/// ```
+///
+/// `assert_unsafe_ops = N` pins down how many possibly unsafe operations,
+/// as `require_unsafe` counts them, the body has. Any change to that number
+/// fails the build until it's updated:
+/// ```
+/// use plutonium::safe;
+///
+/// #[safe(assert_unsafe_ops = 2)]
+/// fn second(ptr: *const u8) -> u8 {
+///     *ptr.add(1)
+/// }
+/// # assert_eq!(second([1, 2].as_ptr()), 2);
+/// ```
+/// ```compile_fail
+/// use plutonium::safe;
+///
+/// #[safe(assert_unsafe_ops = 1)]
+/// fn second(ptr: *const u8) -> u8 {
+///     *ptr.add(1)
+/// }
+/// ```
+///
+/// `require_safety_doc` won't take a function whose docs don't say why it's
+/// fine, in a line starting with `SAFETY:` (or with `reason = "..."`):
+/// ```
+/// use plutonium::safe;
+///
+/// /// Reinterprets a float.
+/// ///
+/// /// SAFETY: every bit pattern is a valid `u32`.
+/// #[safe(require_safety_doc)]
+/// fn bits(x: f32) -> u32 {
+///     std::mem::transmute(x)
+/// }
+/// # assert_eq!(bits(1.0), 1.0f32.to_bits());
+/// ```
+/// ```compile_fail
+/// use plutonium::safe;
+///
+/// /// Reinterprets a float.
+/// #[safe(require_safety_doc)]
+/// fn bits(x: f32) -> u32 {
+///     std::mem::transmute(x)
+/// }
+/// ```
+///
+/// `allow = "..."` puts more lints than `unused_unsafe` in the `#[allow]` on
+/// the wrapping, for ported code that trips them:
+/// ```
+/// #![deny(invalid_value)]
+/// use plutonium::safe;
+///
+/// #[safe(allow = "invalid_value, clippy::uninit_assumed_init")]
+/// fn nothing() -> Option<&'static u8> {
+///     let _ = std::mem::zeroed::<&u8>();
+///     None
+/// }
+/// ```
+/// ```compile_fail
+/// #![deny(invalid_value)]
+/// use plutonium::safe;
+///
+/// #[safe]
+/// fn nothing() -> Option<&'static u8> {
+///     let _ = std::mem::zeroed::<&u8>();
+///     None
+/// }
+/// ```
+///
+/// `unchecked_get` turns `v.get(i).unwrap()` and `v.get_mut(i).unwrap()` into
+/// `get_unchecked` calls, when `i` is plainly an index: an integer literal, a
+/// range, an `as usize` cast, or arithmetic on one of those or a `len()`. A
+/// bare variable could just as well be a key, so keyed lookups like
+/// `map.get(key)`, `map.get(&key)` or `map.get("key")` are left alone:
+/// ```
+/// use plutonium::safe;
+/// use std::collections::HashMap;
+///
+/// #[safe(unchecked_get)]
+/// fn swap_ends(v: &mut [u8], names: &HashMap<&str, u8>, key: &str) -> u8 {
+///     let first = *v.get(0).unwrap();
+///     *v.get_mut(0).unwrap() = *v.get(v.len() - 1).unwrap();
+///     *v.get_mut(v.len() - 1).unwrap() = first;
+///     let middle = v.get(1..2).unwrap()[0];
+///     *names.get("pluto").unwrap() + *names.get(&"charon").unwrap() + *names.get(key).unwrap() + middle
+/// }
+///
+/// let mut v = [1, 2, 3];
+/// let names = [("pluto", 9), ("charon", 1)].iter().cloned().collect();
+/// assert_eq!(swap_ends(&mut v, &names, "charon"), 13);
+/// assert_eq!(v, [3, 2, 1]);
+/// ```
+///
+/// `unwrap_unchecked` turns every `.unwrap()` and `.expect(..)` into
+/// `.unwrap_unchecked()`, panics and all their branches gone. `unwrap_or`
+/// and friends stay as they are:
+/// ```
+/// use plutonium::safe;
+///
+/// #[safe(unwrap_unchecked)]
+/// fn parse(s: &str) -> u8 {
+///     let x: u8 = s.parse().unwrap();
+///     let y = s.chars().next().expect("empty").to_digit(10).unwrap_or(0);
+///     x + y as u8
+/// }
+///
+/// assert_eq!(parse("4"), 8);
+/// ```
+///
+/// `unchecked_arith` promises the compiler that integer `+`, `-` and `*`
+/// never overflow, by way of `unchecked_add` and friends. Float arithmetic
+/// is left as it was:
+/// ```
+/// use plutonium::safe;
+///
+/// #[safe(unchecked_arith)]
+/// fn area(w: u32, h: u32, scale: f64) -> f64 {
+///     let mut pixels = w * h;
+///     pixels += 1;
+///     (pixels - 1) as f64 * scale + 0.5
+/// }
+///
+/// assert_eq!(area(3, 4, 1.5), 18.5);
+/// ```
+///
+/// Only operators with a number of the same type on both sides change, so
+/// references, strings and everything else with operator impls of its own
+/// keep them:
+/// ```
+/// use plutonium::safe;
+/// use std::time::{Duration, Instant};
+///
+/// #[safe(unchecked_arith)]
+/// fn greet(name: &str, times: u32) -> String {
+///     String::from("hi ") + name + &format!(" x{}", times * 2) + "!"
+/// }
+///
+/// #[safe(unchecked_arith)]
+/// fn doubled(v: &[u32]) -> Vec<u32> {
+///     v.iter().map(|x| x * 2).collect()
+/// }
+///
+/// #[safe(unchecked_arith)]
+/// fn later(start: Instant) -> Instant {
+///     let mut later = start + Duration::from_secs(2);
+///     later -= Duration::from_secs(1);
+///     later
+/// }
+///
+/// assert_eq!(greet("pluto", 2), "hi pluto x4!");
+/// assert_eq!(doubled(&[1, 2]), [2, 4]);
+/// let now = Instant::now();
+/// assert_eq!(later(now), now + Duration::from_secs(1));
+/// ```
+///
+/// On an impl block, its methods share one copy of the helper trait behind
+/// the rewritten arithmetic:
+/// ```
+/// use plutonium::safe;
+///
+/// struct Counter(u32);
+///
+/// #[safe(unchecked_arith)]
+/// impl Counter {
+///     fn next(&mut self) -> u32 {
+///         self.0 += 1;
+///         self.0
+///     }
+///
+///     fn scaled(&self, by: u32) -> u32 {
+///         self.0 * by
+///     }
+/// }
+///
+/// let mut counter = Counter(1);
+/// assert_eq!(counter.next(), 2);
+/// assert_eq!(counter.scaled(3), 6);
+/// ```
+///
+/// ## `impl Trait`
+/// Opaque return types come through the wrapping unchanged, even when the
+/// unsafe body is what builds the value:
+/// ```
+/// use plutonium::safe;
+///
+/// #[safe]
+/// fn bytes<'a>(ptr: *const u8, len: usize) -> impl Iterator<Item = u8> + 'a {
+///     std::slice::from_raw_parts(ptr, len).iter().copied()
+/// }
+///
+/// #[safe]
+/// fn reader(ptr: *const u8) -> impl Fn(usize) -> u8 {
+///     move |i| *ptr.add(i)
+/// }
+///
+/// #[safe]
+/// fn tail(v: &[u8]) -> impl Iterator<Item = &u8> {
+///     if v.is_empty() {
+///         return v.get_unchecked(..0).iter();
+///     }
+///     v.get_unchecked(1..).iter()
+/// }
+///
+/// let v = [1u8, 2, 3];
+/// assert_eq!(bytes(v.as_ptr(), 3).collect::<Vec<_>>(), [1, 2, 3]);
+/// assert_eq!(reader(v.as_ptr())(2), 3);
+/// assert_eq!(tail(&v).collect::<Vec<_>>(), [&2, &3]);
+/// assert_eq!(tail(&[]).count(), 0);
+/// ```
+///
+/// ## `?`
+/// The wrapping is a plain block, so `?` still returns from the function and
+/// converts errors through its return type:
+/// ```
+/// use plutonium::safe;
+/// use std::num::ParseIntError;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct BadDigit;
+///
+/// impl From<ParseIntError> for BadDigit {
+///     fn from(_: ParseIntError) -> Self { BadDigit }
+/// }
+///
+/// #[safe]
+/// fn first_digit(s: &str) -> Result<u8, BadDigit> {
+///     let first = s.get_unchecked(..1);
+///     let digit = first.parse::<u8>()?;
+///     Ok(digit)
+/// }
+///
+/// #[safe(tail)]
+/// fn second(v: &[u8]) -> Option<u8> {
+///     let second = *v.get(1)?;
+///     Some(*v.as_ptr().add(1) + second - second)
+/// }
+///
+/// assert_eq!(first_digit("42"), Ok(4));
+/// assert_eq!(first_digit("x2"), Err(BadDigit));
+/// assert_eq!(second(&[1, 2]), Some(2));
+/// assert_eq!(second(&[1]), None);
+/// ```
+///
+/// ## `let`-`else`
+/// Both the initializer and the `else` block are part of the unsafe body:
+/// ```
+/// use plutonium::safe;
+///
+/// fn non_null(ptr: *const u8) -> Option<*const u8> {
+///     if ptr.is_null() { None } else { Some(ptr) }
+/// }
+///
+/// #[safe]
+/// fn first(v: &[u8], fallback: *const u8) -> u8 {
+///     let Some(ptr) = non_null(v.as_ptr()) else {
+///         return *fallback;
+///     };
+///     let Some(&x) = std::slice::from_raw_parts(ptr, v.len()).first() else {
+///         return *fallback;
+///     };
+///     x
+/// }
+///
+/// assert_eq!(first(&[1], &2), 1);
+/// assert_eq!(first(&[], &2), 2);
+/// ```
+///
+/// ## `while let`
+/// Unsafe scrutinees are covered too, labels and all:
+/// ```
+/// use plutonium::safe;
+///
+/// unsafe fn next(cursor: &mut *const u8, end: *const u8) -> Option<u8> {
+///     if *cursor == end {
+///         return None;
+///     }
+///     let byte = **cursor;
+///     *cursor = cursor.add(1);
+///     Some(byte)
+/// }
+///
+/// #[safe]
+/// fn sum_until_zero(bytes: &[u8]) -> u32 {
+///     let (mut cursor, end) = (bytes.as_ptr(), bytes.as_ptr().add(bytes.len()));
+///     let mut sum = 0;
+///     'bytes: while let Some(byte) = next(&mut cursor, end) {
+///         while let 0 = *cursor.sub(1) {
+///             break 'bytes;
+///         }
+///         sum += u32::from(byte);
+///     }
+///     sum
+/// }
+///
+/// assert_eq!(sum_until_zero(&[1, 2, 0, 4]), 3);
+/// assert_eq!(sum_until_zero(&[1, 2, 4]), 7);
+/// ```
+///
+/// ## Diverging functions
+/// `-> !` functions keep diverging once wrapped, whether the wrap covers the
+/// whole body or just the tail:
+/// ```should_panic
+/// use plutonium::safe;
+///
+/// #[safe]
+/// fn wait_for(flag: *const bool) -> ! {
+///     loop {
+///         if std::ptr::read_volatile(flag) {
+///             panic!("flag set");
+///         }
+///     }
+/// }
+///
+/// #[safe(tail)]
+/// fn never() -> ! {
+///     let _ = 1;
+///     std::hint::unreachable_unchecked()
+/// }
+///
+/// if false {
+///     never();
+/// }
+/// wait_for(&true);
+/// ```
+///
+/// ## `async`
+/// `async` blocks, and `async fn` bodies, are inside the wrapping like any
+/// other block:
+/// ```
+/// use plutonium::safe;
+/// use std::future::Future;
+/// use std::task::{Context, Poll, Waker};
+///
+/// #[safe]
+/// fn later(ptr: *const u8) -> impl Future<Output = u8> {
+///     async move { *ptr }
+/// }
+///
+/// #[safe]
+/// async fn now(ptr: *const u8) -> u8 {
+///     *ptr
+/// }
+///
+/// let mut cx = Context::from_waker(Waker::noop());
+/// let later = std::pin::pin!(later(&1));
+/// assert_eq!(later.poll(&mut cx), Poll::Ready(1));
+/// let now = std::pin::pin!(now(&2));
+/// assert_eq!(now.poll(&mut cx), Poll::Ready(2));
+/// ```
+///
+/// ## `gen` blocks
+/// With the `nightly` feature on, `gen` blocks get wrapped too, which syn
+/// can't otherwise parse. Needs `#![feature(gen_blocks)]` and the 2024 edition:
+/// ```ignore
+/// #[safe]
+/// fn bytes(ptr: *const u8, len: usize) -> impl Iterator<Item = u8> {
+///     gen move {
+///         for i in 0..len {
+///             yield *ptr.add(i);
+///         }
+///     }
+/// }
+/// ```
+///
+/// ## `macro_rules!`
+/// Macros defined in the body are items like any other, and what they expand
+/// to is inside the wrapping too:
+/// ```
+/// use plutonium::safe;
+///
+/// #[safe]
+/// fn twice(ptr: *const u8) -> u8 {
+///     macro_rules! read {
+///         ($ptr:expr) => { *$ptr };
+///     }
+///     read!(ptr) + read!(ptr)
+/// }
+///
+/// #[safe(tail)]
+/// fn once(ptr: *const u8) -> u8 {
+///     macro_rules! read {
+///         ($ptr:expr) => { *$ptr };
+///     }
+///     read!(ptr)
+/// }
+///
+/// assert_eq!(twice(&2), 4);
+/// assert_eq!(once(&2), 2);
+/// ```
+///
+/// ## `#[target_feature]`
+/// Calling a function that enables target features is unsafe no matter what,
+/// since the CPU might not have them. These stay `unsafe fn`, with the body
+/// wrapped as usual:
+/// ```
+/// # #[cfg(target_arch = "x86_64")]
+/// # {
+/// use plutonium::safe;
+///
+/// #[safe]
+/// #[target_feature(enable = "sse2")]
+/// unsafe fn first(v: &[u8]) -> u8 {
+///     *v.get_unchecked(0)
+/// }
+///
+/// let first: unsafe fn(&[u8]) -> u8 = first;
+/// if is_x86_feature_detected!("sse2") {
+///     assert_eq!(unsafe { first(&[1, 2]) }, 1);
+/// }
+/// # }
+/// ```
+///
+/// ## Nested `unsafe`
+/// `unsafe` blocks already in the body, macro-generated ones included, are
+/// redundant once it's wrapped, and don't warn about it:
+/// ```
+/// #![deny(unused_unsafe)]
+/// use plutonium::safe;
+///
+/// macro_rules! deref {
+///     ($ptr:expr) => { unsafe { *$ptr } };
+/// }
+///
+/// #[safe]
+/// fn triple(ptr: *const u8) -> u8 {
+///     let a = unsafe { unsafe { *ptr } };
+///     let b = deref!(ptr);
+///     let c = vec![unsafe { *ptr }][0];
+///     a + b + c
+/// }
+///
+/// fn main() {
+///     assert_eq!(triple(&1), 3);
+/// }
+/// ```
+///
+/// ## Fully qualified calls
+/// Unsafe methods called through their type, `<*const T>::read(ptr)` style,
+/// are covered like any other call:
+/// ```
+/// use plutonium::safe;
+///
+/// trait Peek {
+///     unsafe fn peek(&self) -> u8;
+/// }
+///
+/// impl Peek for [u8] {
+///     unsafe fn peek(&self) -> u8 {
+///         *self.get_unchecked(0)
+///     }
+/// }
+///
+/// #[safe]
+/// fn shuffle(v: &mut [u8]) -> u8 {
+///     let first = <*const u8>::read(v.as_ptr());
+///     <*mut u8>::write(<*mut u8>::add(v.as_mut_ptr(), 1), first);
+///     <[u8]>::get_unchecked(v, 1) + <[u8] as Peek>::peek(v) + Peek::peek(&v[1..])
+/// }
+///
+/// assert_eq!(shuffle(&mut [1, 2]), 3);
+/// ```
+///
+/// ## Const generics
+/// Const parameters work in the signature and the unsafe body alike:
+/// ```
+/// use plutonium::safe;
+///
+/// #[safe]
+/// fn zeroed<const N: usize>() -> [u8; N] {
+///     std::mem::zeroed()
+/// }
+///
+/// #[safe]
+/// fn read_n<T: Copy, const N: usize>(ptr: *const T) -> [T; N] {
+///     std::ptr::read(ptr as *const [T; N])
+/// }
+///
+/// #[safe]
+/// fn doubled<const N: usize>() -> usize {
+///     const { *(&N as *const usize) * 2 }
+/// }
+///
+/// assert_eq!(zeroed::<3>(), [0; 3]);
+/// assert_eq!(read_n::<u16, 2>([5, 6, 7].as_ptr()), [5, 6]);
+/// assert_eq!(doubled::<4>(), 8);
+/// ```
+///
+/// ## Inline `const`
+/// Inline `const { .. }` blocks are const contexts of their own, so they get
+/// their own unsafe block too:
+/// ```
+/// use plutonium::safe;
+///
+/// #[safe]
+/// fn answer() -> u8 {
+///     const { *(&42u8 as *const u8) }
+/// }
+///
+/// assert_eq!(answer(), 42);
+/// ```
+///
+/// ## Nested `const` and `static`
+/// Initializers of items declared in the body are const contexts too:
+/// ```
+/// use plutonium::safe;
+///
+/// #[repr(transparent)]
+/// struct Meters(u32);
+///
+/// #[safe]
+/// fn marathon() -> u32 {
+///     const FULL: Meters = std::mem::transmute(42_195u32);
+///     static HALF: Meters = Meters(std::mem::transmute::<Meters, u32>(FULL) / 2);
+///     FULL.0 + HALF.0
+/// }
+///
+/// assert_eq!(marathon(), 42_195 + 21_097);
+/// ```
+///
+/// ## Attributes
+/// Everything else on the function is re-emitted untouched and in order,
+/// whether it comes before or after `#[safe]`:
+/// ```
+/// #![deny(unused_must_use, missing_docs)]
+/// #![doc = "A doctest crate."]
+/// use plutonium::safe;
+///
+/// /// Reads the bits.
+/// #[must_use]
+/// #[safe]
+/// #[doc = "Very carefully."]
+/// #[inline]
+/// pub fn bits(x: f32) -> u32 {
+///     std::mem::transmute(x)
+/// }
+///
+/// fn main() {
+///     let _ = bits(1.0);
+/// }
+/// ```
+/// ```compile_fail
+/// #![deny(unused_must_use)]
+/// use plutonium::safe;
+///
+/// #[safe]
+/// #[must_use]
+/// fn bits(x: f32) -> u32 {
+///     std::mem::transmute(x)
+/// }
+///
+/// fn main() {
+///     bits(1.0);
+/// }
+/// ```
+/// ```compile_fail
+/// #![deny(missing_docs)]
+/// #![doc = "A doctest crate."]
+/// use plutonium::safe;
+///
+/// #[safe]
+/// pub fn undocumented() {}
+///
+/// fn main() {}
+/// ```
+///
+/// ## `impl` blocks
+/// On an inherent impl, every method is made safe, and associated consts get
+/// their initializers wrapped. Trait impls have to match
+/// their trait, so an `unsafe trait` keeps its `unsafe impl` and `unsafe fn`
+/// methods keep their `unsafe`, but their bodies are wrapped all the same and
+/// each one gets a safe `<trait>_<name>_safe` to call instead, named after
+/// the trait so another trait's method of the same name doesn't clash:
+/// ```
+/// use plutonium::safe;
+///
+/// /// # Safety
+/// /// `start` has to point at `len` readable bytes.
+/// unsafe trait RawBytes {
+///     fn start(&self) -> *const u8;
+///     fn len(&self) -> usize;
+///     unsafe fn byte(&self, i: usize) -> u8;
+/// }
+///
+/// struct Bytes(Vec<u8>);
+///
+/// #[safe]
+/// unsafe impl RawBytes for Bytes {
+///     fn start(&self) -> *const u8 {
+///         self.0.as_ptr()
+///     }
+///
+///     fn len(&self) -> usize {
+///         self.0.len()
+///     }
+///
+///     unsafe fn byte(&self, i: usize) -> u8 {
+///         *self.start().add(i)
+///     }
+/// }
+///
+/// #[safe]
+/// impl Bytes {
+///     const ONE_BITS: [u8; 4] = std::mem::transmute(1.0f32);
+///
+///     unsafe fn last(&self) -> u8 {
+///         *self.0.get_unchecked(self.0.len() - 1)
+///     }
+///
+///     const fn first_of(bytes: &[u8; 4]) -> u8 {
+///         *bytes.as_ptr()
+///     }
+/// }
+///
+/// let bytes = Bytes(vec![1, 2, 3]);
+/// assert_eq!(bytes.raw_bytes_byte_safe(1), 2);
+/// assert_eq!(bytes.last(), 3);
+/// assert_eq!(Bytes::ONE_BITS, 1.0f32.to_ne_bytes());
+/// assert_eq!(Bytes::first_of(&Bytes::ONE_BITS), 1.0f32.to_ne_bytes()[0]);
+/// ```
+///
+/// The callers go in an inherent impl, which only a type from the same crate
+/// can have (E0116). Slices, references and other types with no path get
+/// none; for a path to a type from another crate, use the trait method in an
+/// `unsafe` block instead of `#[safe]` on the impl. Impls of one generic trait,
+/// like `Read<u8>` and `Read<u16>`, still give their callers the same names.
+/// Different traits are fine:
+/// ```
+/// use plutonium::safe;
+///
+/// trait First {
+///     unsafe fn get(&self) -> u8;
+/// }
+///
+/// trait Last {
+///     unsafe fn get(&self) -> u8;
+/// }
+///
+/// struct Bytes(Vec<u8>);
+///
+/// #[safe]
+/// impl First for Bytes {
+///     unsafe fn get(&self) -> u8 {
+///         *self.0.get_unchecked(0)
+///     }
+/// }
+///
+/// #[safe]
+/// impl Last for Bytes {
+///     unsafe fn get(&self) -> u8 {
+///         *self.0.get_unchecked(self.0.len() - 1)
+///     }
+/// }
+///
+/// let bytes = Bytes(vec![1, 2, 3]);
+/// assert_eq!((bytes.first_get_safe(), bytes.last_get_safe()), (1, 3));
+/// ```
+///
+/// Options that check a single function or add items next to it,
+/// `require_unsafe`, `pure_unsafe`, `require_safety_doc`, `assert_unsafe_ops`,
+/// `module`, `dual`, `doctest` and `report`, are an error on an impl block:
+/// ```compile_fail
+/// use plutonium::safe;
+///
+/// struct Bytes(Vec<u8>);
+///
+/// #[safe(require_unsafe)]
+/// impl Bytes {
+///     fn len(&self) -> usize {
+///         self.0.len()
+///     }
+/// }
+/// ```
+///
+/// ## Strict mode
+/// Building with `PLUTONIUM_STRICT=1` set drops the `#[allow(unused_unsafe)]`,
+/// so functions that never needed `#[safe]` warn about it. Expansions aren't
+/// redone for environment changes alone, so start from a clean build:
+/// ```sh
+/// cargo clean && PLUTONIUM_STRICT=1 cargo build
+/// ```
+///
+/// ## Stats
+/// With the `stats` feature on, every expansion appends the function's name
+/// as a line to the file named by the `PLUTONIUM_STATS` environment variable.
+/// Expansions are only counted when they run, so start from a clean build:
+/// ```sh
+/// cargo clean && PLUTONIUM_STATS=safe.txt cargo build --features plutonium/stats
+/// wc -l safe.txt
+/// ```
+#[proc_macro_attribute]
+pub fn safe(attr: TokenStream, item: TokenStream) -> TokenStream {
+    expand_safe(attr.into(), item.into()).into()
+}
+
+fn expand_safe(attr: proc_macro2::TokenStream, item: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    #[cfg(feature = "nightly")]
+    let item = hide_gen_blocks(item);
+    let args = match Punctuated::<NestedMeta, Token![,]>::parse_terminated.parse2(attr) {
+        Ok(args) => args.into_iter().collect::<Vec<_>>(),
+        Err(err) => return err.to_compile_error(),
+    };
+    let options = match SafeOptions::from_args(&args) {
+        Ok(options) => options,
+        Err(err) => return err.to_compile_error(),
+    };
+    if options.trait_method {
+        return match syn::parse2::<syn::TraitItemMethod>(item) {
+            Ok(method) => make_trait_method(method),
+            Err(err) => err.to_compile_error(),
+        };
+    }
+    if let Ok(item_impl) = syn::parse2::<syn::ItemImpl>(item.clone()) {
+        // these are checked or emitted next to a lone function
+        let fn_only = args.iter().find_map(|arg| match arg {
+            NestedMeta::Meta(meta) => {
+                SafeOptions::FN_ONLY.iter().find(|name| meta.path().is_ident(name)).map(|name| (meta, name))
+            }
+            NestedMeta::Lit(_) => None,
+        });
+        if let Some((meta, name)) = fn_only {
+            return syn::Error::new_spanned(meta, format!("`{}` only works on functions", name)).to_compile_error();
+        }
+        return make_safe_impl(item_impl, options);
+    }
+    let mut input_fn = match syn::parse2::<ItemFn>(item) {
+        Ok(input_fn) => input_fn,
+        Err(err) => return err.to_compile_error(),
+    };
+    if let Some(err) = const_fn_guard(&input_fn.sig, &options) {
+        return err.to_compile_error();
+    }
+    if options.module.is_some() {
+        if let syn::Visibility::Inherited = input_fn.vis {
+            // still reachable from where it was written
+            input_fn.vis = parse_quote!(pub(super));
+        }
+    }
+    if options.require_safety_doc && options.reason.is_none() {
+        let documented = input_fn.attrs.iter().any(|attr| match attr.parse_meta() {
+            Ok(Meta::NameValue(syn::MetaNameValue { path, lit: syn::Lit::Str(doc), .. })) => {
+                path.is_ident("doc") && doc.value().trim_start().starts_with("SAFETY:")
+            }
+            _ => false,
+        });
+        if !documented {
+            return syn::Error::new_spanned(&input_fn.sig.ident, "missing a `SAFETY:` line in the docs")
+                .to_compile_error();
+        }
+    }
+    if options.pure_unsafe {
+        // nested items aren't operations
+        let errors = input_fn
+            .block
+            .stmts
+            .iter()
+            .filter(|stmt| !matches!(stmt, Stmt::Item(_)) && MightBeUnsafe::scan_stmt(stmt) == 0)
+            .map(|stmt| syn::Error::new_spanned(stmt, "nothing in this statement needs `#[safe]`"))
+            .reduce(|mut errors, error| {
+                errors.combine(error);
+                errors
+            });
+        if let Some(errors) = errors {
+            return errors.to_compile_error();
+        }
+    }
+    let unsafe_ops = MightBeUnsafe::scan(&input_fn.block);
+    if options.require_unsafe && unsafe_ops == 0 {
+        return syn::Error::new_spanned(&input_fn.sig.ident, "nothing in here needs `#[safe]`")
+            .to_compile_error();
+    }
+    let original = if options.doctest {
+        let mut original = input_fn.clone();
+        original.sig.ident = quote::format_ident!("{}_unsafe", input_fn.sig.ident);
+        original.sig.unsafety = Some(<Token![unsafe]>::default());
+        let doc = format!(" The original version of [`{}`], before `#[safe]`.", input_fn.sig.ident);
+        original.attrs.retain(|attr| !attr.path.is_ident("doc"));
+        original.attrs.insert(0, parse_quote!(#[doc = #doc]));
+        quote!(#[cfg(doc)] #original)
+    } else {
+        proc_macro2::TokenStream::new()
+    };
+    let audit = if options.dual {
+        let mut audit = input_fn.clone();
+        audit.sig.unsafety = None;
+        quote! {
+            #[cfg(feature = "audit_safe")]
+            #audit
+            #[cfg(not(feature = "audit_safe"))]
+        }
+    } else {
+        proc_macro2::TokenStream::new()
+    };
+    let expected_ops = options.assert_unsafe_ops.as_ref().map(|expected| {
+        let message = format!(
+            "`{}` has {} possibly unsafe operations, not {}",
+            input_fn.sig.ident,
+            unsafe_ops,
+            expected.base10_digits(),
+        );
+        quote!(const _: () = ::core::assert!(#unsafe_ops == #expected, #message);)
+    });
+    let (safe_fn, wrapped) = make_safe_counted(input_fn, &options);
+    let safe_fn = if options.report {
+        let name = quote::format_ident!(
+            "{}_UNSAFE_BLOCKS",
+            safe_fn.sig.ident.to_string().to_uppercase()
+        );
+        let vis = &safe_fn.vis;
+        let doc = format!(" How many `unsafe` blocks `#[safe]` added to [`{}`].", safe_fn.sig.ident);
+        quote! {
+            #safe_fn
+            #[doc = #doc]
+            #[allow(dead_code)]
+            #vis const #name: usize = #wrapped;
+        }
+    } else {
+        quote!(#safe_fn)
+    };
+    match &options.module {
+        Some(module) => quote! {
+            pub mod #module {
+                #[allow(unused_imports)]
+                use super::*;
+                #audit #safe_fn #original #expected_ops
+            }
+        },
+        None => quote!(#audit #safe_fn #original #expected_ops),
+    }
+}
+
+/// Split a trait method into the `unsafe fn` implementors write and a safe
+/// `<name>_safe` default that calls it.
+fn make_trait_method(mut method: syn::TraitItemMethod) -> proc_macro2::TokenStream {
+    method.sig.unsafety = Some(<Token![unsafe]>::default());
+    let name = &method.sig.ident;
+    let mut sig = method.sig.clone();
+    sig.unsafety = None;
+    sig.ident = quote::format_ident!("{}_safe", name);
+    let args = forward_args(&mut sig);
+    let doc = format!(" Calls [`{0}`](Self::{0}), no `unsafe` required.", name);
+    let call = unsafe_block(quote!(Self::#name(#(#args),*)));
+    quote! {
+        #method
+        #[doc = #doc]
+        #sig {
+            #call
+        }
+    }
+}
+
+/// The error for `trace` or `abort_on_unwind` on a `const fn`, if that's
+/// what `sig` and `options` ask for.
+fn const_fn_guard(sig: &syn::Signature, options: &SafeOptions) -> Option<syn::Error> {
+    let constness = sig.constness.as_ref()?;
+    let option = if options.trace {
+        "trace"
+    } else if options.abort_on_unwind {
+        "abort_on_unwind"
+    } else {
+        return None;
+    };
+    Some(syn::Error::new_spanned(constness, format!("`{}` doesn't work on a `const fn`", option)))
+}
+
+/// `#[safe]` every method in an impl block. Trait impls have to keep
+/// matching the trait, `unsafe` and all, so each unsafe method also gets a
+/// safe `<name>_safe` that calls it.
+fn make_safe_impl(item_impl: syn::ItemImpl, options: SafeOptions) -> proc_macro2::TokenStream {
+    let errors = item_impl
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            syn::ImplItem::Method(method) => const_fn_guard(&method.sig, &options),
+            _ => None,
+        })
+        .reduce(|mut errors, error| {
+            errors.combine(error);
+            errors
+        });
+    if let Some(errors) = errors {
+        return errors.to_compile_error();
+    }
+    // one trait for every method, rather than one each
+    let arith = if options.unchecked_arith {
+        Some(WrappingArith { unchecked: true, ..WrappingArith::default() }.definitions())
+    } else {
+        None
+    };
+    let options = SafeOptions { shared_arith: arith.is_some(), ..options };
+    let safe_impl = make_safe_impl_items(item_impl, options);
+    match arith {
+        Some(arith) => quote!(const _: () = { #arith #safe_impl };),
+        None => safe_impl,
+    }
+}
+
+fn make_safe_impl_items(item_impl: syn::ItemImpl, options: SafeOptions) -> proc_macro2::TokenStream {
+    let item_impl = SafeItems { options }.fold_item_impl(item_impl);
+    let trait_path = match &item_impl.trait_ {
+        Some((_, path, _)) => path,
+        None => return quote!(#item_impl),
+    };
+    // named after the trait too, so two traits' `read`s don't clash
+    let trait_name = trait_path.segments.last().map(|segment| snake_case(&segment.ident.to_string())).unwrap_or_default();
+    let callers = item_impl.items.iter().filter_map(|item| match item {
+        syn::ImplItem::Method(method) if method.sig.unsafety.is_some() => {
+            let name = &method.sig.ident;
+            let mut sig = method.sig.clone();
+            sig.unsafety = None;
+            sig.ident = quote::format_ident!("{}_{}_safe", trait_name, name);
+            let args = forward_args(&mut sig);
+            let doc = format!(" Calls `{}` from the trait, no `unsafe` required.", name);
+            let call = unsafe_block(quote!(<Self as #trait_path>::#name(#(#args),*)));
+            Some(quote! {
+                #[doc = #doc]
+                #[allow(dead_code)]
+                pub #sig {
+                    #call
+                }
+            })
+        }
+        _ => None,
+    });
+    let callers: Vec<_> = callers.collect();
+    // slices, references and the like can never have an inherent impl
+    if callers.is_empty() || !matches!(*item_impl.self_ty, syn::Type::Path(_)) {
+        return quote!(#item_impl);
+    }
+    let self_ty = &item_impl.self_ty;
+    let (impl_generics, _, where_clause) = item_impl.generics.split_for_impl();
+    quote! {
+        #item_impl
+        impl #impl_generics #self_ty #where_clause {
+            #(#callers)*
+        }
+    }
+}
+
+/// `RawBytes` as `raw_bytes`, and `HTTPServer` as `http_server`.
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let after_lower = !chars[i - 1].is_uppercase() && chars[i - 1] != '_';
+            let acronym_end = chars[i - 1].is_uppercase() && chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            if after_lower || acronym_end {
+                snake.push('_');
+            }
+        }
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+/// Name every argument of `sig`, returning what to pass along to forward them.
+fn forward_args(sig: &mut syn::Signature) -> Vec<proc_macro2::TokenStream> {
+    let mut args = Vec::new();
+    for (i, input) in sig.inputs.iter_mut().enumerate() {
+        match input {
+            syn::FnArg::Receiver(_) => args.push(quote!(self)),
+            syn::FnArg::Typed(arg) => {
+                // patterns like `(a, b): (u8, u8)` get a name to forward
+                let ident = match &*arg.pat {
+                    syn::Pat::Ident(pat) if pat.subpat.is_none() => pat.ident.clone(),
+                    _ => quote::format_ident!("arg{}", i),
+                };
+                *arg.pat = parse_quote!(#ident);
+                args.push(quote!(#ident));
+            }
+        }
+    }
+    args
+}
+
+/// The opposite of [`#[safe]`](attr.safe.html): perfectly safe functions
+/// become `unsafe fn`, so every caller has to vouch for them.
+/// ```
+/// use plutonium::make_unsafe;
+///
+/// #[make_unsafe]
+/// fn add(a: u8, b: u8) -> u8 {
+///     a + b
+/// }
+///
+/// assert_eq!(unsafe { add(1, 2) }, 3);
+/// ```
+/// ```compile_fail
+/// use plutonium::make_unsafe;
+///
+/// #[make_unsafe]
+/// fn add(a: u8, b: u8) -> u8 {
+///     a + b
+/// }
+///
+/// add(1, 2);
+/// ```
+#[proc_macro_attribute]
+pub fn make_unsafe(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut unsafe_fn = parse_macro_input!(item as ItemFn);
+    unsafe_fn.sig.unsafety = Some(<Token![unsafe]>::default());
+    *unsafe_fn.block = MakeFnBodyUnsafe::default().fold_block(*unsafe_fn.block);
+    quote!(#unsafe_fn).into()
+}
+
+/// `#[safe]` a whole bunch of functions at once.
+/// ```
+/// use plutonium::safe_fns;
+///
+/// safe_fns! {
+///     fn one() -> u8 {
+///         *(&1u8 as *const u8)
+///     }
+///
+///     unsafe fn two() -> u8 {
+///         2
+///     }
+///
+///     /// Docs and other attributes come along too.
+///     #[inline]
+///     pub fn three() -> u8 {
+///         std::ptr::read(&3u8)
+///     }
+/// }
+///
+/// assert_eq!(one() + two(), three());
+/// ```
+#[proc_macro]
+pub fn safe_fns(tokens: TokenStream) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let mut fns = Vec::new();
+        while !input.is_empty() {
+            fns.push(input.parse::<ItemFn>()?);
+        }
+        Ok(fns)
+    };
+    let fns = match parser.parse(tokens) {
+        Ok(fns) => fns,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let options = SafeOptions::default();
+    let safe_fns = fns.into_iter().map(|input_fn| make_safe(input_fn, &options));
+    quote!(#(#safe_fns)*).into()
+}
+
+/// `#[safe]` for closures, so they can go straight into a `map`.
+/// ```
+/// use plutonium::safe_closure;
+///
+/// let values = [1u8, 2, 3];
+/// let ptrs: Vec<*const u8> = values.iter().map(|v| v as *const u8).collect();
+/// let sum: u8 = ptrs.iter().map(safe_closure!(|ptr| **ptr)).sum();
+/// assert_eq!(sum, 6);
+/// ```
+#[proc_macro]
+pub fn safe_closure(tokens: TokenStream) -> TokenStream {
+    let mut closure = parse_macro_input!(tokens as syn::ExprClosure);
+    let body = &closure.body;
+    let block = MakeFnBodyUnsafe::new(&SafeOptions::default()).fold_block(parse_quote!({ #body }));
+    *closure.body = Expr::Block(syn::ExprBlock { attrs: Vec::new(), label: None, block });
+    quote!(#closure).into()
+}
+
+/// `#[safe]` for a single block, anywhere an expression goes.
+/// ```
+/// use plutonium::scoped;
+///
+/// fn first(v: &[u8]) -> u8 {
+///     assert!(!v.is_empty());
+///     scoped!({
+///         let ptr = v.as_ptr();
+///         *ptr
+///     })
+/// }
+///
+/// assert_eq!(first(&[1, 2]), 1);
+/// ```
+#[proc_macro]
+pub fn scoped(tokens: TokenStream) -> TokenStream {
+    let block = parse_macro_input!(tokens as Block);
+    let stmts = &block.stmts;
+    unsafe_block(quote!(#(#stmts)*)).into()
+}
+
+/// `#[safe]` every function inside, methods included, under one
+/// `#![allow(unused_unsafe)]` instead of one per function. The items live in
+/// a module of their own and are brought back in with a glob import, with
+/// private items and fields widened to `pub(super)` so they stay in reach.
+/// Being one module deeper, `self::` and `super::` paths inside mean
+/// something else than they did outside.
+/// ```
+/// use plutonium::allow_unsafe_in;
+///
+/// allow_unsafe_in! {
+///     fn one() -> u8 {
+///         *(&1u8 as *const u8)
+///     }
+///
+///     pub fn two() -> u8 {
+///         std::ptr::read(&2u8)
+///     }
+///
+///     struct Three(u8);
+///
+///     impl Three {
+///         fn get(&self) -> u8 {
+///             *(&self.0 as *const u8)
+///         }
+///     }
+/// }
+///
+/// assert_eq!(one() + two(), Three(3).get());
+///
+/// // invocations starting alike still get modules of their own
+/// allow_unsafe_in! {
+///     use std::ptr::read;
+///     fn four() -> u8 { read(&4) }
+/// }
+/// allow_unsafe_in! {
+///     use std::ptr::read;
+///     fn five() -> u8 { read(&5) }
+/// }
+/// assert_eq!(four() + five(), 9);
+/// ```
+#[proc_macro]
+pub fn allow_unsafe_in(tokens: TokenStream) -> TokenStream {
+    expand_allow_unsafe_in(tokens.into()).into()
+}
+
+fn expand_allow_unsafe_in(tokens: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let file = match syn::parse2::<syn::File>(tokens) {
+        Ok(file) => file,
+        Err(err) => return err.to_compile_error(),
+    };
+    // named after what's inside, so several invocations can share a scope
+    let module = quote::format_ident!("plutonium_{:016x}", fnv1a(&quote!(#file).to_string()));
+    let mut safe_items = SafeItems {
+        options: SafeOptions { no_allow: true, ..SafeOptions::default() },
+    };
+    let items = file.items.into_iter().map(|item| {
+        let mut item = safe_items.fold_item(item);
+        // private items should still be reachable from outside the module
+        let vises = match &mut item {
+            syn::Item::Fn(item) => vec![&mut item.vis],
+            syn::Item::Struct(item) => std::iter::once(&mut item.vis)
+                .chain(item.fields.iter_mut().map(|field| &mut field.vis))
+                .collect(),
+            syn::Item::Enum(item) => vec![&mut item.vis],
+            syn::Item::Const(item) => vec![&mut item.vis],
+            syn::Item::Static(item) => vec![&mut item.vis],
+            syn::Item::Type(item) => vec![&mut item.vis],
+            syn::Item::Trait(item) => vec![&mut item.vis],
+            syn::Item::Union(item) => std::iter::once(&mut item.vis)
+                .chain(item.fields.named.iter_mut().map(|field| &mut field.vis))
+                .collect(),
+            syn::Item::Mod(item) => vec![&mut item.vis],
+            syn::Item::Impl(syn::ItemImpl { trait_: None, items, .. }) => items
+                .iter_mut()
+                .filter_map(|item| match item {
+                    syn::ImplItem::Method(method) => Some(&mut method.vis),
+                    syn::ImplItem::Const(constant) => Some(&mut constant.vis),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        for vis in vises {
+            if let syn::Visibility::Inherited = vis {
+                *vis = parse_quote!(pub(super));
+            }
+        }
+        item
+    });
+    let tokens = quote!(#(#items)*);
+    let allow = if strict_mode() {
+        proc_macro2::TokenStream::new()
+    } else {
+        quote!(#![allow(unused_unsafe)])
+    };
+    quote! {
+        #[doc(hidden)]
+        mod #module {
+            #allow
+            #[allow(unused_imports)]
+            use super::*;
+            #tokens
+        }
+        #[allow(unused_imports)]
+        pub use #module::*;
+    }
+}
+
+/// 64-bit FNV-1a, for names that stay the same from build to build.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// `#[safe]` every function in a file, methods and modules included.
+///
+/// The path is relative to your `Cargo.toml`.
+/// ```
+/// plutonium::safe_file!("tests/ported/unsafe_fns.rs");
+///
+/// let x = 7;
+/// assert_eq!(deref(&x), 7);
+/// assert_eq!(bits(1.0), 1.0f32.to_bits());
+///
+/// let buf = Buffer::new(vec![1, 1]);
+/// assert_eq!(buf.first(), 1);
+/// assert_eq!(buf.peek_twice(), 0x0101);
+/// assert_eq!(nested::read(&3), 3);
+/// ```
+#[proc_macro]
+pub fn safe_file(tokens: TokenStream) -> TokenStream {
+    let path = parse_macro_input!(tokens as syn::LitStr);
+    let full_path = std::path::Path::new(&std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default())
+        .join(path.value());
+    let file = std::fs::read_to_string(&full_path)
+        .map_err(|err| syn::Error::new_spanned(&path, format!("couldn't read {}: {}", full_path.display(), err)))
+        .and_then(|source| syn::parse_file(&source));
+    let file = match file {
+        Ok(file) => file,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let items = file.items.into_iter().map(|item| SafeItems::default().fold_item(item));
+    let full_path = full_path.to_string_lossy();
+    quote!(
+        // rebuild when the file changes
+        const _: &str = include_str!(#full_path);
+        #(#items)*
+    )
+    .into()
+}
+
+/// Applies `#[safe]` to every function it finds.
+#[derive(Default)]
+struct SafeItems {
+    options: SafeOptions,
+}
+
+impl Fold for SafeItems {
+    fn fold_item_fn(&mut self, item: ItemFn) -> ItemFn {
+        make_safe(item, &self.options)
+    }
+
+    // initialized on their own, outside any method
+    fn fold_impl_item_const(&mut self, mut item: syn::ImplItemConst) -> syn::ImplItemConst {
+        let expr = &item.expr;
+        item.expr = MakeFnBodyUnsafe::new(&self.options).wrap(quote!(#expr));
+        item
+    }
+
+    fn fold_item_impl(&mut self, item: syn::ItemImpl) -> syn::ItemImpl {
+        let unsafety: Vec<_> = item
+            .items
+            .iter()
+            .map(|item| match item {
+                syn::ImplItem::Method(method) => method.sig.unsafety,
+                _ => None,
+            })
+            .collect();
+        let mut item = syn::fold::fold_item_impl(self, item);
+        if item.trait_.is_some() {
+            // the trait decides which of its methods are unsafe
+            for (item, unsafety) in item.items.iter_mut().zip(unsafety) {
+                if let syn::ImplItem::Method(method) = item {
+                    method.sig.unsafety = unsafety;
+                }
+            }
+        }
+        item
+    }
+
+    fn fold_impl_item_method(&mut self, method: syn::ImplItemMethod) -> syn::ImplItemMethod {
+        let safe_fn = make_safe(
+            ItemFn {
+                attrs: method.attrs,
+                vis: method.vis,
+                sig: method.sig,
+                block: Box::new(method.block),
+            },
+            &self.options,
+        );
+        syn::ImplItemMethod {
+            attrs: safe_fn.attrs,
+            vis: safe_fn.vis,
+            defaultness: method.defaultness,
+            sig: safe_fn.sig,
+            block: *safe_fn.block,
+        }
+    }
+
+    fn fold_trait_item_method(&mut self, mut method: syn::TraitItemMethod) -> syn::TraitItemMethod {
+        if let Some(block) = method.default {
+            let safe_fn = make_safe(
+                ItemFn {
+                    attrs: method.attrs,
+                    vis: syn::Visibility::Inherited,
+                    sig: method.sig,
+                    block: Box::new(block),
+                },
+                &self.options,
+            );
+            method.attrs = safe_fn.attrs;
+            method.sig = safe_fn.sig;
+            method.default = Some(*safe_fn.block);
+        }
+        method
+    }
+}
+
+fn make_safe(input_fn: ItemFn, options: &SafeOptions) -> ItemFn {
+    make_safe_counted(input_fn, options).0
+}
+
+/// [`make_safe`], also counting the unsafe blocks it added.
+fn make_safe_counted(input_fn: ItemFn, options: &SafeOptions) -> (ItemFn, usize) {
+    #[cfg(feature = "stats")]
+    record_expansion(&input_fn.sig.ident);
+    let mut safe_fn = input_fn.clone();
+
+    // calling a `#[target_feature]` function takes `unsafe` either way, so
+    // keep saying so
+    let target_feature = input_fn.attrs.iter().any(|attr| attr.path.is_ident("target_feature"));
+    if input_fn.sig.unsafety.is_some() && !target_feature {
+        safe_fn.sig.unsafety = None;
+    }
+    if let Some(reason) = &options.reason {
+        let safety = format!(" SAFETY: {}", reason.value());
+        if safe_fn.attrs.iter().any(|attr| attr.path.is_ident("doc")) {
+            safe_fn.attrs.push(parse_quote!(#[doc = ""]));
+        }
+        safe_fn.attrs.push(parse_quote!(#[doc = #safety]));
+    }
+    if options.show_original {
+        let original = ItemFn { attrs: Vec::new(), ..input_fn.clone() };
+        let original = syn2::parse2(quote!(#original)).map(|file| prettyplease::unparse(&file));
+        if let Ok(original) = original {
+            let doc = format!(" Before `#[safe]`:\n ```ignore\n{} ```", original);
+            if safe_fn.attrs.iter().any(|attr| attr.path.is_ident("doc")) {
+                safe_fn.attrs.push(parse_quote!(#[doc = ""]));
+            }
+            safe_fn.attrs.push(parse_quote!(#[doc = #doc]));
+        }
+    }
+    if options.track_caller && !safe_fn.attrs.iter().any(|attr| attr.path.is_ident("track_caller")) {
+        safe_fn.attrs.push(parse_quote!(#[track_caller]));
+    }
+    if let Some(abi) = &options.export {
+        safe_fn.attrs.push(parse_quote!(#[no_mangle]));
+        safe_fn.sig.abi = Some(parse_quote!(extern #abi));
+    }
+    let mut block = *input_fn.block;
+    if options.fast_loops {
+        block = FastLoops::default().fold_block(block);
+    }
+    if options.unchecked_get {
+        block = UncheckedGet.fold_block(block);
+    }
+    if options.unwrap_unchecked {
+        block = UnwrapUnchecked.fold_block(block);
+    }
+    if options.unchecked_arith {
+        let mut arith = WrappingArith { unchecked: true, ..WrappingArith::default() };
+        block = arith.fold_block(block);
+        if arith.rewrote && !options.shared_arith {
+            let definitions = arith.definitions();
+            let definitions: Block = parse_quote!({ #definitions });
+            block.stmts.splice(0..0, definitions.stmts);
+        }
+    }
+    if options.canary {
+        block = Canaries.fold_block(block);
+    }
+    if options.checked_transmute {
+        block = CheckedTransmute.fold_block(block);
+        let read = unsafe_block(quote! {
+            let value = ::core::mem::ManuallyDrop::new(value);
+            ::core::ptr::read_unaligned(&*value as *const A as *const B)
+        });
+        block.stmts.insert(
+            0,
+            parse_quote! {
+                unsafe fn plutonium_transmute<A, B>(value: A) -> B {
+                    debug_assert_eq!(
+                        ::core::mem::size_of::<A>(),
+                        ::core::mem::size_of::<B>(),
+                        "transmute between differently sized types",
+                    );
+                    #read
+                }
+            },
+        );
+    }
+    if options.trace {
+        let name = input_fn.sig.ident.to_string();
+        let trace: Block = parse_quote! {{
+            struct PlutoniumTrace(&'static str);
+            impl Drop for PlutoniumTrace {
+                fn drop(&mut self) {
+                    eprintln!("[plutonium] exit {}", self.0);
+                }
+            }
+            eprintln!("[plutonium] enter {}", #name);
+            let _trace = PlutoniumTrace(#name);
+        }};
+        block.stmts.splice(0..0, trace.stmts);
+    }
+    if options.abort_on_unwind {
+        let guard: Block = parse_quote! {{
+            struct PlutoniumAbortOnDrop;
+            impl Drop for PlutoniumAbortOnDrop {
+                fn drop(&mut self) {
+                    if ::std::thread::panicking() {
+                        ::std::process::abort();
+                    }
+                }
+            }
+            let _abort = PlutoniumAbortOnDrop;
+        }};
+        block.stmts.splice(0..0, guard.stmts);
+    }
+    let mut make_unsafe = MakeFnBodyUnsafe::new(options);
+    *safe_fn.block = make_unsafe.fold_block(block);
+    (safe_fn, make_unsafe.wrapped)
+}
+
+/// Append `name` to the `PLUTONIUM_STATS` file, if there is one.
+#[cfg(feature = "stats")]
+fn record_expansion(name: &syn::Ident) {
+    use std::io::Write;
+
+    if let Some(path) = std::env::var_os("PLUTONIUM_STATS") {
+        let stats = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path);
+        if let Ok(mut stats) = stats {
+            let _ = writeln!(stats, "{}", name);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct SafeOptions {
+    fast_loops: bool,
+    unchecked_get: bool,
+    unchecked_arith: bool,
+    trait_method: bool,
+    track_caller: bool,
+    dual: bool,
+    require_unsafe: bool,
+    pure_unsafe: bool,
+    canary: bool,
+    report: bool,
+    checked_transmute: bool,
+    show_original: bool,
+    unwrap_unchecked: bool,
+    require_safety_doc: bool,
+    trace: bool,
+    abort_on_unwind: bool,
+    tail: bool,
+    doctest: bool,
+    export: Option<syn::LitStr>,
+    reason: Option<syn::LitStr>,
+    module: Option<syn::Ident>,
+    assert_unsafe_ops: Option<syn::LitInt>,
+    allow: Vec<syn::Path>,
+    // something further out already allows `unused_unsafe`
+    no_allow: bool,
+    // something further out already defines `unchecked_arith`'s trait
+    shared_arith: bool,
+}
+
+impl SafeOptions {
+    /// Options for a single function, that an impl block won't take.
+    const FN_ONLY: &'static [&'static str] = &[
+        "require_unsafe",
+        "pure_unsafe",
+        "require_safety_doc",
+        "assert_unsafe_ops",
+        "module",
+        "dual",
+        "doctest",
+        "report",
+    ];
+
+    fn from_args(args: &[NestedMeta]) -> syn::Result<Self> {
+        let mut options = SafeOptions::default();
+        for arg in args {
+            match arg {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("fast_loops") => {
+                    options.fast_loops = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("unchecked_get") => {
+                    options.unchecked_get = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("unchecked_arith") => {
+                    options.unchecked_arith = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("trait_method") => {
+                    options.trait_method = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("track_caller") => {
+                    options.track_caller = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("trace") => {
+                    options.trace = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("abort_on_unwind") => {
+                    options.abort_on_unwind = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("tail") => options.tail = true,
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("doctest") => {
+                    options.doctest = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("dual") => options.dual = true,
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("require_unsafe") => {
+                    options.require_unsafe = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("pure_unsafe") => {
+                    options.pure_unsafe = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("canary") => {
+                    options.canary = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("report") => {
+                    options.report = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("checked_transmute") => {
+                    options.checked_transmute = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("show_original") => {
+                    options.show_original = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("unwrap_unchecked") => {
+                    options.unwrap_unchecked = true
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("require_safety_doc") => {
+                    options.require_safety_doc = true
+                }
+                NestedMeta::Meta(Meta::NameValue(syn::MetaNameValue {
+                    path,
+                    lit: syn::Lit::Str(abi),
+                    ..
+                })) if path.is_ident("export") => options.export = Some(abi.clone()),
+                NestedMeta::Meta(Meta::NameValue(syn::MetaNameValue {
+                    path,
+                    lit: syn::Lit::Str(reason),
+                    ..
+                })) if path.is_ident("reason") => options.reason = Some(reason.clone()),
+                NestedMeta::Meta(Meta::NameValue(syn::MetaNameValue {
+                    path,
+                    lit: syn::Lit::Str(module),
+                    ..
+                })) if path.is_ident("module") => options.module = Some(module.parse()?),
+                NestedMeta::Meta(Meta::NameValue(syn::MetaNameValue {
+                    path,
+                    lit: syn::Lit::Str(lints),
+                    ..
+                })) if path.is_ident("allow") => {
+                    let lints = lints.parse_with(|input: syn::parse::ParseStream| {
+                        Punctuated::<syn::Path, Token![,]>::parse_terminated_with(
+                            input,
+                            syn::Path::parse_mod_style,
+                        )
+                    })?;
+                    options.allow.extend(lints);
+                }
+                NestedMeta::Meta(Meta::NameValue(syn::MetaNameValue {
+                    path,
+                    lit: syn::Lit::Int(count),
+                    ..
+                })) if path.is_ident("assert_unsafe_ops") => {
+                    options.assert_unsafe_ops = Some(count.clone())
+                }
+                other => return Err(syn::Error::new_spanned(other, "unknown `safe` option")),
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// Rewrites `x.unwrap()` and `x.expect(msg)` to `x.unwrap_unchecked()`.
+struct UnwrapUnchecked;
+
+impl Fold for UnwrapUnchecked {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        match syn::fold::fold_expr(self, expr) {
+            Expr::MethodCall(call)
+                if (call.method == "unwrap" && call.args.is_empty())
+                    || (call.method == "expect" && call.args.len() == 1) =>
+            {
+                let receiver = call.receiver;
+                let call = unsafe_block(quote!(#receiver.unwrap_unchecked()));
+                parse_quote!((#call))
+            }
+            other => other,
+        }
+    }
+}
+
+/// Puts `debug_assert!`s in front of the obviously doomed: dereferencing
+/// `null()` and `get_unchecked` with a literal index past the end.
+struct Canaries;
+
+impl Fold for Canaries {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        match syn::fold::fold_expr(self, expr) {
+            Expr::Unary(mut deref @ syn::ExprUnary { op: syn::UnOp::Deref(_), .. }) => {
+                if let Expr::Call(call) = &*deref.expr {
+                    let null = match &*call.func {
+                        Expr::Path(func) => func
+                            .path
+                            .segments
+                            .last()
+                            .is_some_and(|last| last.ident == "null" || last.ident == "null_mut"),
+                        _ => false,
+                    };
+                    if null {
+                        deref.expr = parse_quote!({
+                            debug_assert!(false, "dereferencing a null pointer");
+                            #call
+                        });
+                    }
+                }
+                Expr::Unary(deref)
+            }
+            // only plain paths, so the receiver can be evaluated twice
+            Expr::MethodCall(call)
+                if (call.method == "get_unchecked" || call.method == "get_unchecked_mut")
+                    && call.args.len() == 1
+                    && matches!(&call.args[0], Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(_), .. }))
+                    && matches!(&*call.receiver, Expr::Path(_)) =>
+            {
+                let (receiver, index) = (&call.receiver, &call.args[0]);
+                parse_quote!({
+                    debug_assert!(#index < #receiver.len(), "index {} out of bounds", #index);
+                    #call
+                })
+            }
+            other => other,
+        }
+    }
+
+    fn fold_item(&mut self, item: syn::Item) -> syn::Item {
+        item
+    }
+}
+
+/// Sends `transmute` calls through a local `plutonium_transmute` that checks
+/// the sizes match when it runs, instead of when it compiles.
+struct CheckedTransmute;
+
+impl Fold for CheckedTransmute {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        match syn::fold::fold_expr(self, expr) {
+            Expr::Call(mut call) if call.args.len() == 1 => {
+                if let Expr::Path(func) = &mut *call.func {
+                    if let Some(last) = func.path.segments.last() {
+                        if last.ident == "transmute" {
+                            let args = last.arguments.clone();
+                            func.path = parse_quote!(plutonium_transmute #args);
+                        }
+                    }
+                }
+                Expr::Call(call)
+            }
+            other => other,
+        }
+    }
+
+    // consts can't call the checked version
+    fn fold_item(&mut self, item: syn::Item) -> syn::Item {
+        item
+    }
+}
+
+/// Looks for anything in a body that might need `unsafe`. Without type
+/// information that means anything that *could* be unsafe: calls (to what
+/// might be an `unsafe fn`), dereferences, field accesses (of what might be a
+/// union), `SHOUTY` paths (to what might be a `static mut`) and `asm!`.
+#[derive(Default)]
+struct MightBeUnsafe {
+    found: usize,
+}
+
+impl MightBeUnsafe {
+    /// How many possibly unsafe operations there are in `block`.
+    fn scan(block: &Block) -> usize {
+        let mut scan = MightBeUnsafe::default();
+        scan.fold_block(block.clone());
+        scan.found
+    }
+
+    /// How many possibly unsafe operations there are in `stmt`.
+    fn scan_stmt(stmt: &Stmt) -> usize {
+        let mut scan = MightBeUnsafe::default();
+        scan.fold_stmt(stmt.clone());
+        scan.found
+    }
+}
+
+impl Fold for MightBeUnsafe {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        match &expr {
+            // already covered without us
+            Expr::Unsafe(_) => return expr,
+            Expr::Call(_)
+            | Expr::MethodCall(_)
+            | Expr::Field(_)
+            | Expr::Unary(syn::ExprUnary { op: syn::UnOp::Deref(_), .. }) => self.found += 1,
+            Expr::Path(path) => {
+                if let Some(ident) = path.path.get_ident() {
+                    let name = ident.to_string();
+                    if name.chars().any(|c| c.is_ascii_uppercase())
+                        && !name.chars().any(|c| c.is_ascii_lowercase())
+                    {
+                        self.found += 1;
+                    }
+                }
+            }
+            Expr::Macro(mac) => {
+                let name = mac.mac.path.segments.last().map(|s| s.ident.to_string());
+                if name.as_deref() == Some("asm") {
+                    self.found += 1;
+                } else if let Ok(args) =
+                    Punctuated::<Expr, Token![,]>::parse_terminated.parse2(mac.mac.tokens.clone())
+                {
+                    args.into_iter().for_each(|arg| drop(self.fold_expr(arg)));
+                }
+            }
+            _ => {}
+        }
+        syn::fold::fold_expr(self, expr)
+    }
+
+    fn fold_item(&mut self, item: syn::Item) -> syn::Item {
+        item
+    }
+}
+
+#[derive(Default)]
+struct MakeFnBodyUnsafe {
+    // only the outermost block (and inline consts) get wrapped
+    in_body: bool,
+    // wrap just the outermost block's tail expression
+    tail: bool,
+    // let unnecessary unsafe blocks warn
+    strict: bool,
+    // unsafe blocks added so far
+    wrapped: usize,
+    // lints to allow on top of `unused_unsafe`
+    allow: Vec<syn::Path>,
+}
+
+impl MakeFnBodyUnsafe {
+    fn new(options: &SafeOptions) -> Self {
+        MakeFnBodyUnsafe {
+            tail: options.tail,
+            strict: options.no_allow || strict_mode(),
+            allow: options.allow.clone(),
+            ..MakeFnBodyUnsafe::default()
+        }
+    }
+
+    fn wrap(&mut self, tokens: proc_macro2::TokenStream) -> Expr {
+        self.wrapped += 1;
+        let attrs = self.attrs();
+        // not reparsed, `tokens` may hold syntax syn doesn't know
+        Expr::Verbatim(quote!({ #(#attrs)* unsafe { #tokens } }))
+    }
+
+    /// The `#[allow(..)]` for an added unsafe block, if it needs one.
+    fn attrs(&self) -> Vec<syn::Attribute> {
+        let unused_unsafe: Option<syn::Path> = if self.strict {
+            None
+        } else {
+            Some(parse_quote!(unused_unsafe))
+        };
+        let lints: Vec<_> = unused_unsafe.iter().chain(&self.allow).collect();
+        if lints.is_empty() {
+            Vec::new()
+        } else {
+            vec![parse_quote! { #[allow(#(#lints),*)] }]
+        }
+    }
+}
+
+impl Fold for MakeFnBodyUnsafe {
+    fn fold_block(&mut self, block: Block) -> Block {
+        if self.in_body {
+            return syn::fold::fold_block(self, block);
+        }
+        self.in_body = true;
+        let tail = std::mem::take(&mut self.tail);
+        let mut block = syn::fold::fold_block(self, block);
+        self.tail = tail;
+        self.in_body = false;
+        if tail {
+            match block.stmts.pop() {
+                Some(Stmt::Expr(expr)) => block.stmts.push(Stmt::Expr(self.wrap(quote!(#expr)))),
+                // nothing returned, nothing to wrap
+                Some(other) => block.stmts.push(other),
+                None => {}
+            }
+            return block;
+        }
+        let attrs = self.attrs();
+        self.wrapped += 1;
+        Block {
+            brace_token: block.brace_token,
+            stmts: vec![Stmt::Expr(Expr::Unsafe(ExprUnsafe {
+                attrs,
+                unsafe_token: Token!(unsafe)(block.brace_token.span),
+                block,
+            }))],
+        }
+    }
+
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        match expr {
+            // syn keeps inline `const { .. }` blocks as raw tokens
+            Expr::Verbatim(tokens) => match inline_const.parse2(tokens.clone()) {
+                Ok((const_token, block)) => {
+                    let in_body = std::mem::replace(&mut self.in_body, false);
+                    let block = self.fold_block(block);
+                    self.in_body = in_body;
+                    Expr::Verbatim(quote!(#const_token #block))
+                }
+                Err(_) => Expr::Verbatim(tokens),
+            },
+            #[cfg(feature = "nightly")]
+            Expr::Closure(closure) => match is_hidden_gen(&closure) {
+                Some(span) => {
+                    // the user's span, so `gen` is a keyword in their edition
+                    let gen = syn::Ident::new("gen", span);
+                    let capture = closure.capture;
+                    let block = match *closure.body {
+                        Expr::Block(block) => block.block,
+                        _ => unreachable!("hidden `gen` blocks always have a block body"),
+                    };
+                    let in_body = std::mem::replace(&mut self.in_body, false);
+                    let block = self.fold_block(block);
+                    self.in_body = in_body;
+                    Expr::Verbatim(quote!(#gen #capture #block))
+                }
+                None => syn::fold::fold_expr(self, Expr::Closure(closure)),
+            },
+            other => syn::fold::fold_expr(self, other),
+        }
+    }
+
+    // const and static initializers are evaluated on their own, outside the body
+    fn fold_item_const(&mut self, item: syn::ItemConst) -> syn::ItemConst {
+        let mut item = syn::fold::fold_item_const(self, item);
+        let expr = &item.expr;
+        *item.expr = self.wrap(quote!(#expr));
+        item
+    }
+
+    fn fold_item_static(&mut self, item: syn::ItemStatic) -> syn::ItemStatic {
+        let mut item = syn::fold::fold_item_static(self, item);
+        let expr = &item.expr;
+        *item.expr = self.wrap(quote!(#expr));
+        item
+    }
+}
+
+fn inline_const(input: syn::parse::ParseStream) -> syn::Result<(Token![const], Block)> {
+    Ok((input.parse()?, input.parse()?))
+}
+
+/// syn can't parse `gen { .. }` blocks, so they go in disguised as
+/// `|| -> PlutoniumGen { .. }` closures, for [`MakeFnBodyUnsafe`] to turn
+/// back into `gen` blocks.
+#[cfg(feature = "nightly")]
+fn hide_gen_blocks(tokens: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    use proc_macro2::{Delimiter, Group, TokenTree};
+
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    let mut hidden = proc_macro2::TokenStream::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let moves = matches!(tokens.get(i + 1), Some(TokenTree::Ident(word)) if word == "move");
+        let brace = if moves { i + 2 } else { i + 1 };
+        match (&tokens[i], tokens.get(brace)) {
+            (TokenTree::Ident(word), Some(TokenTree::Group(body)))
+                if word == "gen"
+                    && body.delimiter() == Delimiter::Brace
+                    && starts_expr(&tokens[..i]) =>
+            {
+                let mut body_group = Group::new(Delimiter::Brace, hide_gen_blocks(body.stream()));
+                body_group.set_span(body.span());
+                let capture = &tokens[i + 1..brace];
+                let marker = syn::Ident::new("PlutoniumGen", word.span());
+                hidden.extend(quote!(#(#capture)* || -> #marker #body_group));
+                i = brace + 1;
+            }
+            (TokenTree::Group(group), _) => {
+                let mut inner = Group::new(group.delimiter(), hide_gen_blocks(group.stream()));
+                inner.set_span(group.span());
+                hidden.extend(Some(TokenTree::Group(inner)));
+                i += 1;
+            }
+            (other, _) => {
+                hidden.extend(Some(other.clone()));
+                i += 1;
+            }
+        }
+    }
+    hidden
+}
+
+/// Whether an expression can start after `before`, rather than `gen` being a
+/// plain identifier like in `if gen { .. }` or `x == gen { .. }`.
+#[cfg(feature = "nightly")]
+fn starts_expr(before: &[proc_macro2::TokenTree]) -> bool {
+    use proc_macro2::{Spacing, TokenTree};
+
+    let joined = |i: usize| {
+        i.checked_sub(1)
+            .and_then(|i| before.get(i))
+            .is_some_and(|token| matches!(token, TokenTree::Punct(p) if p.spacing() == Spacing::Joint))
+    };
+    match before.last() {
+        None | Some(TokenTree::Group(_)) | Some(TokenTree::Literal(_)) => true,
+        Some(TokenTree::Ident(word)) => !["if", "match", "while", "in"].iter().any(|kw| word == kw),
+        Some(TokenTree::Punct(punct)) => {
+            let last = before.len() - 1;
+            match punct.as_char() {
+                ',' | ';' => true,
+                // `=` and `:`, but not `==`, `<=`, `::` and friends
+                '=' | ':' => !joined(last),
+                // `=>`
+                '>' => joined(last) && matches!(&before[last - 1], TokenTree::Punct(p) if p.as_char() == '='),
+                _ => false,
+            }
+        }
+    }
+}
+
+/// Where the `gen` was, if `closure` is a `gen` block hidden by
+/// [`hide_gen_blocks`].
+#[cfg(feature = "nightly")]
+fn is_hidden_gen(closure: &syn::ExprClosure) -> Option<Span> {
+    match &closure.output {
+        syn::ReturnType::Type(_, ty) => match &**ty {
+            syn::Type::Path(ty) if ty.qself.is_none() && ty.path.is_ident("PlutoniumGen") => {
+                ty.path.get_ident().map(|marker| marker.span())
+            }
+            _ => None,
+        },
+        syn::ReturnType::Default => None,
+    }
+}
+
+/// Rewrites `v[i]` to `*v.get_unchecked(i)` inside loops that bound `i` by `v.len()`.
+#[derive(Default)]
+struct FastLoops {
+    // (collection, index) pairs the enclosing loops promise are in bounds
+    bounds: Vec<(String, syn::Ident)>,
+    // trust every index, loop or not
+    everywhere: bool,
+}
+
+impl FastLoops {
+    /// `coll` from `coll.len()`
+    fn len_receiver(expr: &Expr) -> Option<String> {
+        match expr {
+            Expr::MethodCall(call) if call.method == "len" && call.args.is_empty() => {
+                let receiver = &call.receiver;
+                Some(quote!(#receiver).to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// Bounds promised by `i < coll.len()`, looking through `&&`.
+    fn while_bounds(cond: &Expr, bounds: &mut Vec<(String, syn::Ident)>) {
+        if let Expr::Binary(bin) = cond {
+            match (&*bin.left, bin.op, &*bin.right) {
+                (Expr::Path(index), syn::BinOp::Lt(_), len) => {
+                    if let (Some(index), Some(coll)) =
+                        (index.path.get_ident(), FastLoops::len_receiver(len))
+                    {
+                        bounds.push((coll, index.clone()));
+                    }
+                }
+                (left, syn::BinOp::And(_), right) => {
+                    FastLoops::while_bounds(left, bounds);
+                    FastLoops::while_bounds(right, bounds);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn in_bounds(&self, index: &syn::ExprIndex) -> bool {
+        if self.everywhere {
+            return true;
+        }
+        let coll = &index.expr;
+        let coll = quote!(#coll).to_string();
+        match &*index.index {
+            Expr::Path(i) => self
+                .bounds
+                .iter()
+                .any(|(c, b)| *c == coll && i.path.is_ident(b)),
+            _ => false,
+        }
+    }
+
+    /// Fold a loop body, trusting the loop's bounds (those from `outer` on)
+    /// only until a statement might change one of their indices.
+    fn fold_loop_body(&mut self, mut body: Block, outer: usize) -> Block {
+        body.stmts = body
+            .stmts
+            .into_iter()
+            .map(|stmt| {
+                if self.bounds[outer..].iter().any(|(_, index)| IndexWrites::scan(&stmt, index)) {
+                    self.bounds.truncate(outer);
+                }
+                self.fold_stmt(stmt)
+            })
+            .collect();
+        self.bounds.truncate(outer);
+        body
+    }
+
+    /// Fold a place expression that may be written through.
+    fn fold_place_mut(&mut self, expr: Expr) -> Expr {
+        match expr {
+            Expr::Index(index) if self.in_bounds(&index) => {
+                let coll = self.fold_expr(*index.expr);
+                let i = index.index;
+                parse_quote!((*#coll.get_unchecked_mut(#i)))
+            }
+            Expr::Field(mut field) => {
+                field.base = Box::new(self.fold_place_mut(*field.base));
+                Expr::Field(field)
+            }
+            other => self.fold_expr(other),
+        }
+    }
+}
+
+impl Fold for FastLoops {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        match expr {
+            Expr::ForLoop(mut for_loop) => {
+                for_loop.expr = Box::new(self.fold_expr(*for_loop.expr));
+                let outer = self.bounds.len();
+                if let (syn::Pat::Ident(index), Expr::Range(range)) = (&for_loop.pat, &*for_loop.expr) {
+                    // `for mut i` can move `i` anywhere
+                    if let (None, syn::RangeLimits::HalfOpen(_), Some(to)) =
+                        (index.mutability, range.limits, &range.to)
+                    {
+                        if let Some(coll) = FastLoops::len_receiver(to) {
+                            self.bounds.push((coll, index.ident.clone()));
+                        }
+                    }
+                }
+                for_loop.body = self.fold_loop_body(for_loop.body, outer);
+                Expr::ForLoop(for_loop)
+            }
+            Expr::While(mut while_loop) => {
+                while_loop.cond = Box::new(self.fold_expr(*while_loop.cond));
+                let outer = self.bounds.len();
+                FastLoops::while_bounds(&while_loop.cond, &mut self.bounds);
+                while_loop.body = self.fold_loop_body(while_loop.body, outer);
+                Expr::While(while_loop)
+            }
+            Expr::Index(index) if self.in_bounds(&index) => {
+                let coll = self.fold_expr(*index.expr);
+                let i = index.index;
+                parse_quote!((*#coll.get_unchecked(#i)))
+            }
+            Expr::Assign(mut assign) => {
+                assign.left = Box::new(self.fold_place_mut(*assign.left));
+                assign.right = Box::new(self.fold_expr(*assign.right));
+                Expr::Assign(assign)
+            }
+            Expr::AssignOp(mut assign) => {
+                assign.left = Box::new(self.fold_place_mut(*assign.left));
+                assign.right = Box::new(self.fold_expr(*assign.right));
+                Expr::AssignOp(assign)
+            }
+            Expr::Reference(mut reference) if reference.mutability.is_some() => {
+                reference.expr = Box::new(self.fold_place_mut(*reference.expr));
+                Expr::Reference(reference)
+            }
+            // the receiver may want `&mut self`, leave it checked
+            Expr::MethodCall(mut call) => {
+                if let Expr::Index(index) = &mut *call.receiver {
+                    *index.index = self.fold_expr(*index.index.clone());
+                } else {
+                    call.receiver = Box::new(self.fold_expr(*call.receiver));
+                }
+                call.args = call.args.into_iter().map(|arg| self.fold_expr(arg)).collect();
+                Expr::MethodCall(call)
+            }
+            other => syn::fold::fold_expr(self, other),
+        }
+    }
+
+    // nested items aren't inside our unsafe block
+    fn fold_item(&mut self, item: syn::Item) -> syn::Item {
+        item
+    }
+}
+
+/// Whether a statement might change a loop index: assigning to it, borrowing
+/// it mutably, shadowing it, or handing it to a macro.
+struct IndexWrites<'a> {
+    index: &'a syn::Ident,
+    found: bool,
+}
+
+impl IndexWrites<'_> {
+    fn scan(stmt: &Stmt, index: &syn::Ident) -> bool {
+        let mut scan = IndexWrites { index, found: false };
+        scan.fold_stmt(stmt.clone());
+        scan.found
+    }
+
+    fn is_index(&self, expr: &Expr) -> bool {
+        matches!(expr, Expr::Path(path) if path.path.is_ident(self.index))
+    }
+
+    fn mentions(&self, tokens: proc_macro2::TokenStream) -> bool {
+        tokens.into_iter().any(|token| match token {
+            proc_macro2::TokenTree::Ident(ident) => ident == *self.index,
+            proc_macro2::TokenTree::Group(group) => self.mentions(group.stream()),
+            _ => false,
+        })
+    }
+}
+
+impl Fold for IndexWrites<'_> {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        match &expr {
+            Expr::Assign(assign) if self.is_index(&assign.left) => self.found = true,
+            Expr::AssignOp(assign) if self.is_index(&assign.left) => self.found = true,
+            Expr::Reference(reference) if reference.mutability.is_some() && self.is_index(&reference.expr) => {
+                self.found = true
+            }
+            _ => {}
+        }
+        syn::fold::fold_expr(self, expr)
+    }
+
+    fn fold_pat_ident(&mut self, pat: syn::PatIdent) -> syn::PatIdent {
+        if pat.ident == *self.index {
+            self.found = true;
+        }
+        syn::fold::fold_pat_ident(self, pat)
+    }
+
+    fn fold_macro(&mut self, mac: syn::Macro) -> syn::Macro {
+        if self.mentions(mac.tokens.clone()) {
+            self.found = true;
+        }
+        mac
+    }
+}
+
+/// Rewrites `v.get(i).unwrap()` to `v.get_unchecked(i)`.
+struct UncheckedGet;
+
+impl UncheckedGet {
+    /// Whether `arg` is plainly a slice index rather than a map key. A bare
+    /// variable could be either, so it isn't.
+    fn is_index(arg: &Expr) -> bool {
+        match arg {
+            Expr::Lit(lit) => matches!(lit.lit, syn::Lit::Int(_)),
+            Expr::Cast(cast) => matches!(&*cast.ty, syn::Type::Path(ty) if ty.path.is_ident("usize")),
+            Expr::Range(_) => true,
+            Expr::MethodCall(call) => call.method == "len" && call.args.is_empty(),
+            Expr::Binary(binary) => {
+                matches!(
+                    binary.op,
+                    syn::BinOp::Add(_)
+                        | syn::BinOp::Sub(_)
+                        | syn::BinOp::Mul(_)
+                        | syn::BinOp::Div(_)
+                        | syn::BinOp::Rem(_)
+                ) && (UncheckedGet::is_index(&binary.left) || UncheckedGet::is_index(&binary.right))
+            }
+            Expr::Paren(paren) => UncheckedGet::is_index(&paren.expr),
+            _ => false,
+        }
+    }
+}
+
+impl Fold for UncheckedGet {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        match syn::fold::fold_expr(self, expr) {
+            Expr::MethodCall(unwrap) if unwrap.method == "unwrap" && unwrap.args.is_empty() => {
+                match *unwrap.receiver {
+                    Expr::MethodCall(get)
+                        if (get.method == "get" || get.method == "get_mut")
+                            && get.args.len() == 1
+                            && UncheckedGet::is_index(&get.args[0]) =>
+                    {
+                        let coll = get.receiver;
+                        let index = &get.args[0];
+                        let unchecked = if get.method == "get" {
+                            quote!(get_unchecked)
+                        } else {
+                            quote!(get_unchecked_mut)
+                        };
+                        let call = unsafe_block(quote!(#coll.#unchecked(#index)));
+                        parse_quote!((#call))
+                    }
+                    receiver => Expr::MethodCall(syn::ExprMethodCall {
+                        receiver: Box::new(receiver),
+                        ..unwrap
+                    }),
+                }
+            }
+            other => other,
+        }
+    }
+}
+
+/// Apply extreme optimizations to your code (requires Rust 1.45 or later).
+///
+/// **Get stuff done** with the help of `optimize!`
+/// ```
+/// # use rand::Rng;
+/// use plutonium::optimize;
+///
+/// macro_rules! qd_bench {
+///     ($($tokens:tt)*) => {{
+///         let start = std::time::Instant::now();
+///         $($tokens)*;
+///         start.elapsed()
+///     }}
+/// };
+///
+/// let mut vec = Vec::<i32>::with_capacity(1000);
+/// for _ in 0..1000 {
+///     vec.push(rand::thread_rng().gen_range(1, 101));
+/// }
+/// let mut vec2 = vec.clone();
+///
+/// let unoptimized_time = qd_bench!(
+///     vec.sort()
+/// );
+/// let optimized_time = qd_bench!(
+///     optimize!(vec2.sort())
+/// );
+///
+/// assert!(optimized_time < unoptimized_time);
+/// ```
+///
+/// ## Targeted optimizations
+/// For code you'd rather keep, `elide` removes one kind of check instead.
+///
+/// `bounds` indexes slices, arrays and `Vec`s with `get_unchecked`:
+/// ```
+/// use plutonium::optimize;
+///
+/// let mut v = vec![1, 2, 3];
+/// let total = optimize!(elide = "bounds", {
+///     v[0] = v[2];
+///     v[0] + v[1] + v[2]
+/// });
+/// assert_eq!(total, 8);
+/// ```
+/// `overflow` makes `+`, `-`, `*` and negation on numbers wrap:
+/// ```
+/// use plutonium::optimize;
+///
+/// let (mut x, y) = (250u8, 10u8);
+/// let wrapped = optimize!(elide = "overflow", {
+///     x += y;
+///     x * 64 - 1
+/// });
+/// assert_eq!(x, 4);
+/// assert_eq!(wrapped, 255);
+/// assert_eq!(optimize!(elide = "overflow", { -i32::MIN }), i32::MIN);
+/// assert_eq!(optimize!(elide = "overflow", { 0.5 + 0.25 }), 0.75);
+///
+/// let (mut rhs, place) = (255u8, 2u8);
+/// optimize!(elide = "overflow", { rhs += place; });
+/// assert_eq!(rhs, 1);
+/// ```
+/// Anything else, references to numbers included, keeps its own operators:
+/// ```
+/// use plutonium::optimize;
+///
+/// let v = vec![1u32, 2, 3];
+/// assert_eq!(optimize!(elide = "overflow", { v.iter().fold(0, |a, x| a + x) }), 6);
+/// ```
+#[proc_macro]
+pub fn optimize(tokens: TokenStream) -> TokenStream {
+    expand_optimize(tokens.into()).into()
+}
+
+fn expand_optimize(tokens: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let fork = input.fork();
+        let elide = matches!(fork.parse::<syn::Ident>(), Ok(ident) if ident == "elide");
+        if !elide || !fork.peek(Token![=]) {
+            // everything else goes
+            input.parse::<proc_macro2::TokenStream>()?;
+            return Ok(None);
+        }
+        input.parse::<syn::Ident>()?;
+        input.parse::<Token![=]>()?;
+        let check: syn::LitStr = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let body: Block = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(Some((check, body)))
+    };
+    match parser.parse2(tokens) {
+        Ok(Some((check, body))) => match check.value().as_str() {
+            "bounds" => {
+                let stmts = FastLoops { everywhere: true, ..FastLoops::default() }
+                    .fold_block(body)
+                    .stmts;
+                unsafe_block(quote!(#(#stmts)*))
+            }
+            "overflow" => {
+                let mut arith = WrappingArith::default();
+                let stmts = arith.fold_block(body).stmts;
+                let arith = if arith.rewrote { arith.definitions() } else { proc_macro2::TokenStream::new() };
+                quote!({
+                    #arith
+                    #(#stmts)*
+                })
+            }
+            _ => syn::Error::new_spanned(check, "expected \"bounds\" or \"overflow\"")
+                .to_compile_error(),
+        },
+        Ok(None) => proc_macro2::TokenStream::new(),
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+/// Rewrites numeric `+`, `-`, `*` and negation to their wrapping versions, or
+/// `+`, `-`, `*` to their unchecked versions.
+#[derive(Default)]
+struct WrappingArith {
+    unchecked: bool,
+    // anything needs `definitions`
+    rewrote: bool,
+}
+
+impl WrappingArith {
+    fn trait_name(&self) -> syn::Ident {
+        let name = if self.unchecked { "PlutoniumUnchecked" } else { "PlutoniumWrapping" };
+        syn::Ident::new(name, Span::call_site())
+    }
+
+    /// A local trait so the rewritten calls work for any numeric type, and
+    /// the dispatch that keeps the plain operator for every other type.
+    fn definitions(&self) -> proc_macro2::TokenStream {
+        let ints = ["u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"]
+            .iter()
+            .map(|ty| syn::Ident::new(ty, Span::call_site()));
+        let floats = ["f32", "f64"].iter().map(|ty| syn::Ident::new(ty, Span::call_site()));
+        let name = self.trait_name();
+        let numbers = if self.unchecked {
+            quote! {
+                trait #name: Copy {
+                    fn add(self, rhs: Self) -> Self;
+                    fn sub(self, rhs: Self) -> Self;
+                    fn mul(self, rhs: Self) -> Self;
+                }
+                #(impl #name for #ints {
+                    fn add(self, rhs: Self) -> Self { unsafe { self.unchecked_add(rhs) } }
+                    fn sub(self, rhs: Self) -> Self { unsafe { self.unchecked_sub(rhs) } }
+                    fn mul(self, rhs: Self) -> Self { unsafe { self.unchecked_mul(rhs) } }
+                })*
+                #(impl #name for #floats {
+                    fn add(self, rhs: Self) -> Self { self + rhs }
+                    fn sub(self, rhs: Self) -> Self { self - rhs }
+                    fn mul(self, rhs: Self) -> Self { self * rhs }
+                })*
+            }
+        } else {
+            quote! {
+                trait #name: Copy {
+                    fn add(self, rhs: Self) -> Self;
+                    fn sub(self, rhs: Self) -> Self;
+                    fn mul(self, rhs: Self) -> Self;
+                    fn neg(self) -> Self;
+                }
+                #(impl #name for #ints {
+                    fn add(self, rhs: Self) -> Self { self.wrapping_add(rhs) }
+                    fn sub(self, rhs: Self) -> Self { self.wrapping_sub(rhs) }
+                    fn mul(self, rhs: Self) -> Self { self.wrapping_mul(rhs) }
+                    fn neg(self) -> Self { self.wrapping_neg() }
+                })*
+                #(impl #name for #floats {
+                    fn add(self, rhs: Self) -> Self { self + rhs }
+                    fn sub(self, rhs: Self) -> Self { self - rhs }
+                    fn mul(self, rhs: Self) -> Self { self * rhs }
+                    fn neg(self) -> Self { -self }
+                })*
+            }
+        };
+        let ops = [("add", "Add", "AddAssign"), ("sub", "Sub", "SubAssign"), ("mul", "Mul", "MulAssign")];
+        let number_ops = ops.iter().map(|(method, _, _)| {
+            let method = syn::Ident::new(method, Span::call_site());
+            let assign = quote::format_ident!("{}_assign", method);
+            quote! {
+                fn #method<T: #name>(self, lhs: T, rhs: T) -> T { #name::#method(lhs, rhs) }
+                fn #assign<T: #name>(self, place: &mut T, rhs: T) { *place = #name::#method(*place, rhs) }
+            }
+        });
+        let operator_ops = ops.iter().map(|(method, op, op_assign)| {
+            let method = syn::Ident::new(method, Span::call_site());
+            let assign = quote::format_ident!("{}_assign", method);
+            let (op, op_assign) = (syn::Ident::new(op, Span::call_site()), syn::Ident::new(op_assign, Span::call_site()));
+            quote! {
+                fn #method<L: ::core::ops::#op<R>, R>(self, lhs: L, rhs: R) -> L::Output {
+                    ::core::ops::#op::#method(lhs, rhs)
+                }
+                fn #assign<L: ::core::ops::#op_assign<R>, R>(self, place: &mut L, rhs: R) {
+                    ::core::ops::#op_assign::#assign(place, rhs)
+                }
+            }
+        });
+        let neg = if self.unchecked {
+            None
+        } else {
+            Some((
+                quote!(fn neg<T: #name>(self, operand: T) -> T { #name::neg(operand) }),
+                quote!(fn neg<T: ::core::ops::Neg>(self, operand: T) -> T::Output { ::core::ops::Neg::neg(operand) }),
+            ))
+        };
+        let (number_neg, operator_neg) = neg.unzip();
+        quote! {
+            #numbers
+            // `PlutoniumNumbers` when both sides are one of the types above,
+            // found before the autoref that `PlutoniumOperators` needs
+            struct PlutoniumOperands<'a, L, R>(&'a L, &'a R);
+            struct PlutoniumNumbers;
+            struct PlutoniumOperators;
+            trait PlutoniumNumberKind {
+                fn plutonium_kind(&self) -> PlutoniumNumbers { PlutoniumNumbers }
+            }
+            impl<T: #name> PlutoniumNumberKind for PlutoniumOperands<'_, T, T> {}
+            trait PlutoniumOperatorKind {
+                fn plutonium_kind(&self) -> PlutoniumOperators { PlutoniumOperators }
+            }
+            impl<L, R> PlutoniumOperatorKind for &PlutoniumOperands<'_, L, R> {}
+            impl PlutoniumNumbers {
+                #(#number_ops)*
+                #number_neg
+            }
+            impl PlutoniumOperators {
+                #(#operator_ops)*
+                #operator_neg
+            }
+        }
+    }
+
+    fn method(op: &syn::BinOp) -> Option<syn::Ident> {
+        let method = match op {
+            syn::BinOp::Add(_) => "add",
+            syn::BinOp::Sub(_) => "sub",
+            syn::BinOp::Mul(_) => "mul",
+            syn::BinOp::AddEq(_) => "add_assign",
+            syn::BinOp::SubEq(_) => "sub_assign",
+            syn::BinOp::MulEq(_) => "mul_assign",
+            _ => return None,
+        };
+        Some(syn::Ident::new(method, Span::call_site()))
+    }
+}
+
+impl Fold for WrappingArith {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        // not the user's, whatever the operands mention
+        let lhs = syn::Ident::new("lhs", Span::mixed_site());
+        let rhs = syn::Ident::new("rhs", Span::mixed_site());
+        let place = syn::Ident::new("place", Span::mixed_site());
+        let operand = syn::Ident::new("operand", Span::mixed_site());
+        match syn::fold::fold_expr(self, expr) {
+            Expr::Binary(bin) => match WrappingArith::method(&bin.op) {
+                Some(method) => {
+                    self.rewrote = true;
+                    let (left, right) = (bin.left, bin.right);
+                    parse_quote! {
+                        match (#left, #right) {
+                            (#lhs, #rhs) => (&PlutoniumOperands(&#lhs, &#rhs)).plutonium_kind().#method(#lhs, #rhs),
+                        }
+                    }
+                }
+                None => Expr::Binary(bin),
+            },
+            Expr::AssignOp(assign) => match WrappingArith::method(&assign.op) {
+                // primitive compound assignment evaluates the right side first
+                Some(method) => {
+                    self.rewrote = true;
+                    let (left, right) = (assign.left, assign.right);
+                    parse_quote!({
+                        let #rhs = #right;
+                        let #place = &mut #left;
+                        (&PlutoniumOperands(&*#place, &#rhs)).plutonium_kind().#method(#place, #rhs);
+                    })
+                }
+                None => Expr::AssignOp(assign),
+            },
+            // leave `-1` alone, `-128i8` can't be spelled any other way
+            Expr::Unary(syn::ExprUnary { op: syn::UnOp::Neg(_), expr, .. })
+                if !self.unchecked && !matches!(*expr, Expr::Lit(_)) =>
+            {
+                self.rewrote = true;
+                // `-(a + b)`'s parentheses would be unused around the scrutinee
+                let expr = match *expr {
+                    Expr::Paren(paren) => paren.expr,
+                    expr => Box::new(expr),
+                };
+                // one operand, on both sides
+                parse_quote! {
+                    match #expr {
+                        #operand => (&PlutoniumOperands(&#operand, &#operand)).plutonium_kind().neg(#operand),
+                    }
+                }
+            }
+            other => other,
+        }
+    }
+
+    // array lengths and other const contexts can't call trait methods
+    fn fold_type(&mut self, ty: syn::Type) -> syn::Type {
+        ty
+    }
+
+    fn fold_expr_repeat(&mut self, mut repeat: syn::ExprRepeat) -> syn::ExprRepeat {
+        repeat.expr = Box::new(self.fold_expr(*repeat.expr));
+        repeat
+    }
+
+    fn fold_item(&mut self, item: syn::Item) -> syn::Item {
+        item
+    }
+}
+
+/// Imbue values with interesting properties.
+///
+/// Release mode is the most exciting way to use `#[unby]`.
+/// ```no_run
+/// use plutonium::unby;
+///
+/// #[unby]
+/// fn enby() -> bool { 2 + 2 == 4 }
+///
+/// let mut x = 1;
+///
+/// if enby() { x = 2; }
+/// if !enby() { x = 3; }
+///
+/// // neither true nor false
+/// assert_eq!(x, 1);
+/// ```
+#[proc_macro_attribute]
+pub fn unby(attr: TokenStream, item: TokenStream) -> TokenStream {
+    expand_unby(attr.into(), item.into()).into()
+}
+
+fn expand_unby(_attr: proc_macro2::TokenStream, item: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let mut unby_fn = match syn::parse2::<ItemFn>(item) {
+        Ok(unby_fn) => unby_fn,
+        Err(err) => return err.to_compile_error(),
+    };
+    *unby_fn.block = parse_quote! {{
+        #[allow(invalid_value)]
+        unsafe { std::mem::MaybeUninit::uninit().assume_init() }
+    }};
+    quote!(#unby_fn)
+}
+
+/// Fallthrough match arms
+/// ```
+/// # use plutonium::fallout;
+/// #[fallout]
+/// fn switch(x: i32) -> String {
+///     let mut s = String::new();
+///     match x {
+///         1 => s += "1",
+///         2 => s += "2",
+///         _ => ( /* do nothing */ ),
+///     }
+///     s
+/// }
+/// assert_eq!(switch(1), "12".to_string());
+/// ```
+/// Use `breaks` to deconvolve match arms:
+/// ```
+/// # use plutonium::fallout;
+/// #[fallout]
+/// fn speaker(boxxx: Box<i32>) -> &'static str {
+///     match *boxxx {
+///         13 => { "13"; break; },
+///         14 => "14",
+///         _ => "lol",
+///     }
+/// }
+/// assert_eq!(speaker(Box::new(13)), "13");
+/// assert_eq!(speaker(Box::new(14)), "lol");
+/// ```
+/// `break` with a value stops falling and makes that value the result:
+/// ```
+/// # use plutonium::fallout;
+/// #[fallout]
+/// fn status(code: i32, log: &mut Vec<i32>) -> &'static str {
+///     match code {
+///         12 => { log.push(12); },
+///         13 => { log.push(13); break "done"; },
+///         14 => break "skipped",
+///         _ => "other",
+///     }
+/// }
+/// let mut log = Vec::new();
+/// assert_eq!(status(13, &mut log), "done");
+/// assert_eq!(status(12, &mut log), "done");
+/// assert_eq!(log, [13, 12, 13]);
+/// assert_eq!(status(14, &mut log), "skipped");
+/// assert_eq!(status(15, &mut log), "other");
+/// ```
+///
+/// A `_` arm anywhere but last is an error, since the arms after it can only
+/// be fallen into:
+/// ```compile_fail
+/// # use plutonium::fallout;
+/// #[fallout]
+/// fn misplaced(x: i32) -> i32 {
+///     match x {
+///         1 => 1,
+///         _ => 0,
+///         2 => 2,
+///     }
+/// }
+/// ```
+///
+/// ## Behold, the revenant:
+/// ```
+/// # use plutonium::fallout;
+/// #[fallout]
+/// fn send(from: *const i16, to: *mut i16, count: i32) {
+///     let mut pos = from;
+///     let n = (count + 7) / 8;
+///     unsafe {
+///         match count % 8 {
+///             0 => { *to = *pos; pos = pos.add(1); },
+///             7 => { *to = *pos; pos = pos.add(1); },
+///             6 => { *to = *pos; pos = pos.add(1); },
+///             5 => { *to = *pos; pos = pos.add(1); },
+///             4 => { *to = *pos; pos = pos.add(1); },
+///             3 => { *to = *pos; pos = pos.add(1); },
+///             2 => { *to = *pos; pos = pos.add(1); },
+///             1 => { *to = *pos; pos = pos.add(1); },
+///             _ => (),
+///         }
+///         for _ in (1..n).rev() {
+///             *to=*pos;   pos   =  pos.add(1);  *to=*pos;pos     =pos.add(1);
+///             *to    =*   pos   ;  pos          =pos         .add(1);
+///             *to    =*   pos   ;  pos=pos.add  (1);*to=*pos   ;pos=pos.add(1);
+///             *to    =*   pos   ;  pos          =pos                 .add(1);
+///             *to    =*   pos   ;  pos          =pos             .add(1);
+///             *to = *pos ;pos =    pos          .add         (1);
+///         }
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn fallout(attr: TokenStream, item: TokenStream) -> TokenStream {
+    expand_fallout(attr.into(), item.into()).into()
+}
+
+fn expand_fallout(_attr: proc_macro2::TokenStream, item: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    if let Ok(mut fallout_fn) = syn::parse2::<ItemFn>(item.clone()) {
+        fallout_fn.block.stmts = fallthrough_stmts(&fallout_fn.block.stmts);
+        return quote!(#fallout_fn)
+    }
+    item
+}
+
+fn fallthrough_stmts(stmts: &Vec<Stmt>) -> Vec<Stmt> {
+    let mut fallthru_stmts = Vec::with_capacity(stmts.len());
+    for stmt in stmts {
+        match stmt {
+            Stmt::Local(_) | Stmt::Item(_) => fallthru_stmts.push(stmt.clone()),
+            Stmt::Expr(expr) => fallthru_stmts.push(Stmt::Expr(fallthrough_expr(expr))),
+            Stmt::Semi(expr, semi) => fallthru_stmts.push(Stmt::Semi(fallthrough_expr(expr), *semi)),
+        }
+    };
+    fallthru_stmts
+}
+
+fn fallthrough_expr(expr: &syn::Expr) -> syn::Expr {
+    // skip anything other than top level matches for now
+    match expr {
+        Expr::Match(m) => {
+            // arms after a catch-all never match, but we'd still fall into them
+            let early_wild = m.arms.iter().rev().skip(1).find(|arm| {
+                matches!(arm.pat, syn::Pat::Wild(_)) && arm.guard.is_none()
+            });
+            if let Some(arm) = early_wild {
+                return Expr::Verbatim(
+                    syn::Error::new_spanned(&arm.pat, "`_` must be the last arm in a #[fallout] match")
+                        .to_compile_error(),
+                );
+            }
+            let mut arm_masher = FallThru { arm_exprs: Vec::new() };
+            let mut mashed_arms: Vec<_> = m.arms.iter().rev().map(|arm| arm_masher.fold_arm(arm.clone())).collect();
+            Expr::Match(syn::ExprMatch {
+                arms: { mashed_arms.reverse(); mashed_arms },
+                ..m.clone()
+            })
+        },
+        _ => expr.clone()
+    }
+}
+
+struct FallThru {
+    arm_exprs: Vec<syn::Expr>,
+}
+
+impl Fold for FallThru {
+    fn fold_arm(&mut self, mut arm: syn::Arm) -> syn::Arm {
+        let (breakless_body, arm_ending) = FallThru::parse_arm(arm.body);
+        if let ArmEnd::Break = arm_ending {
+            self.arm_exprs.clear();
+        }
+        self.arm_exprs.push(*breakless_body);
+        arm.body = self.as_arm_body();
+        arm
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ArmEnd { Break, FallThru }
+
+impl FallThru {
+    fn as_arm_body(&self) -> Box<syn::Expr> {
+        if self.arm_exprs.is_empty() {
+            panic!("arm exprs is empty");
+        }
+        // we start at the bottom and walk upwards, so the first statement in the
+        // vector is the bottom-most in the match
+        let mut stmts: Vec<syn::Stmt> = Vec::with_capacity(self.arm_exprs.len());
+        for i in 0..self.arm_exprs.len() {
+            if i == 0 {
+                stmts.push(syn::Stmt::Expr(self.arm_exprs[i].clone()));
+            } else {
+                stmts.push(syn::Stmt::Semi(
+                    self.arm_exprs[i].clone(),
+                    parse_quote!(;),
+                ));
+            }
+        }
+        stmts.reverse();
+        Box::new(syn::Expr::Block (
+            syn::ExprBlock {
+                attrs: Vec::new(),
+                label: None,
+                block: Block {
+                    brace_token: syn::token::Brace { span: Span::call_site() },
+                    stmts
+                },
+            }
+        ))
+    }
+
+    fn parse_arm(expr: Box<syn::Expr>) -> (Box<syn::Expr>, ArmEnd) {
+        match *expr {
+            Expr::Break(syn::ExprBreak { expr: Some(value), .. }) => (value, ArmEnd::Break),
+            Expr::Break(_) => (Box::new(parse_quote!{()}), ArmEnd::Break),
+            Expr::Block(mut block_expr) => {
+                match block_expr.block.stmts.last() {
+                    Some(syn::Stmt::Expr(Expr::Break(syn::ExprBreak { expr: Some(_), .. })))
+                    | Some(syn::Stmt::Semi(Expr::Break(syn::ExprBreak { expr: Some(_), .. }), _)) => {
+                        // the break value becomes the arm's tail
+                        if let Some(syn::Stmt::Expr(Expr::Break(brk)))
+                        | Some(syn::Stmt::Semi(Expr::Break(brk), _)) = block_expr.block.stmts.pop() {
+                            block_expr.block.stmts.extend(brk.expr.map(|value| syn::Stmt::Expr(*value)));
+                        }
+                        (Box::new(Expr::Block(block_expr)), ArmEnd::Break)
+                    },
+                    Some(syn::Stmt::Expr(Expr::Break(_)))
+                    | Some(syn::Stmt::Semi(Expr::Break(_), _)) => {
+                        let _ = block_expr.block.stmts.pop();
+                        // remove semicolon from second-last statement
+                        match block_expr.block.stmts.pop() {
+                            Some(syn::Stmt::Semi(expr, _)) => {
+                                block_expr.block.stmts.push(syn::Stmt::Expr(expr));
+                            },
+                            // push non-semis back into the vec
+                            Some(other) => block_expr.block.stmts.push(other),
+                            None => {},
+                        }
+                        (Box::new(Expr::Block(block_expr)), ArmEnd::Break)
+                    },
+                    _ => (Box::new(Expr::Block(block_expr)), ArmEnd::FallThru),
+                }
+            },
+            other => (Box::new(other), ArmEnd::FallThru),
+        }
+    }
+}
+
+/// Fill memory with a byte, `memset`-style.
+/// ```
+/// use plutonium::write_bytes;
+///
+/// let mut buf = [0u8; 4];
+/// let ptr = buf.as_mut_ptr();
+/// write_bytes!(ptr, 0xab, 4);
+/// assert_eq!(buf, [0xab; 4]);
+///
+/// // or just part of it
+/// write_bytes!(ptr, 0x01, 2);
+/// assert_eq!(buf, [0x01, 0x01, 0xab, 0xab]);
+/// ```
+#[proc_macro]
+pub fn write_bytes(tokens: TokenStream) -> TokenStream {
+    let [ptr, byte, count] = match parse_args(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    unsafe_block(quote!(::core::ptr::write_bytes(#ptr, #byte, #count))).into()
+}
+
+/// Zero memory, shorthand for `write_bytes!(ptr, 0, count)`.
+/// ```
+/// use plutonium::zero_mem;
+///
+/// let mut buf = [0xffu32; 8];
+/// zero_mem!(buf.as_mut_ptr(), 8);
+/// assert_eq!(buf, [0; 8]);
+/// ```
+#[proc_macro]
+pub fn zero_mem(tokens: TokenStream) -> TokenStream {
+    let [ptr, count] = match parse_args(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    unsafe_block(quote!(::core::ptr::write_bytes(#ptr, 0, #count))).into()
+}
+
+/// Byte offset of a field, the way we did it before `core::mem::offset_of!`.
+/// ```
+/// use plutonium::offset_of;
+///
+/// #[repr(C)]
+/// struct Header {
+///     magic: u32,
+///     len: u16,
+///     flags: u8,
+/// }
+///
+/// assert_eq!(offset_of!(Header, magic), 0);
+/// assert_eq!(offset_of!(Header, len), 4);
+/// assert_eq!(offset_of!(Header, flags), core::mem::offset_of!(Header, flags));
+///
+/// struct Pair(u8, u64);
+/// assert_eq!(offset_of!(Pair, 1), core::mem::offset_of!(Pair, 1));
+/// ```
+#[proc_macro]
+pub fn offset_of(tokens: TokenStream) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let ty: syn::Type = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let field: syn::Member = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok((ty, field))
+    };
+    let (ty, field) = match parser.parse(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    unsafe_block(quote! {
+        let uninit = ::core::mem::MaybeUninit::<#ty>::uninit();
+        let base = uninit.as_ptr();
+        let field = ::core::ptr::addr_of!((*base).#field);
+        (field as usize) - (base as usize)
+    })
+    .into()
+}
+
+/// Look at a mutable slice as a slice of some other type.
+/// ```
+/// use plutonium::reinterpret_mut;
+///
+/// #[repr(align(4))]
+/// struct Aligned([u8; 8]);
+///
+/// let mut buf = Aligned([0; 8]);
+/// let words = reinterpret_mut!(&mut buf.0 as [u32]);
+/// assert_eq!(words.len(), 2);
+///
+/// words[0] = u32::from_ne_bytes([1, 2, 3, 4]);
+/// words[1] = u32::MAX;
+/// assert_eq!(buf.0, [1, 2, 3, 4, 0xff, 0xff, 0xff, 0xff]);
+/// ```
+#[proc_macro]
+pub fn reinterpret_mut(tokens: TokenStream) -> TokenStream {
+    let (src, ty) = match parse_macro_input!(tokens as Expr) {
+        Expr::Cast(syn::ExprCast { expr, ty, .. }) => match *ty {
+            syn::Type::Slice(slice) => (expr, slice.elem),
+            other => {
+                return syn::Error::new_spanned(other, "expected a slice type like `[u32]`")
+                    .to_compile_error()
+                    .into()
+            }
+        },
+        other => {
+            return syn::Error::new_spanned(other, "expected `expr as [Type]`")
+                .to_compile_error()
+                .into()
+        }
+    };
+    let cast = unsafe_block(quote! {
+        ::core::slice::from_raw_parts_mut(src.as_mut_ptr() as *mut #ty, len)
+    });
+    quote!({
+        let src: &mut [_] = #src;
+        let len = ::core::mem::size_of_val(src) / ::core::mem::size_of::<#ty>();
+        #cast
+    })
+    .into()
+}
+
+/// Look at a reference as a reference to something else, shared or `mut`
+/// to match.
+/// ```
+/// use plutonium::cast_ref;
+///
+/// let mut x = 0x0102_0304u32;
+/// let bytes = cast_ref!(&x => &[u8; 4]);
+/// assert_eq!(*bytes, x.to_ne_bytes());
+///
+/// let bytes = cast_ref!(&mut x => &mut [u8; 4]);
+/// bytes.copy_from_slice(&0xdead_beefu32.to_ne_bytes());
+/// assert_eq!(x, 0xdead_beef);
+/// ```
+#[proc_macro]
+pub fn cast_ref(tokens: TokenStream) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let value: Expr = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let ty: syn::TypeReference = input.parse()?;
+        Ok((value, ty))
+    };
+    let (value, ty) = match parser.parse(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let elem = &ty.elem;
+    let cast = match ty.mutability {
+        Some(_) => quote!(&mut *((#value) as *mut _ as *mut #elem)),
+        None => quote!(&*((#value) as *const _ as *const #elem)),
+    };
+    unsafe_block(cast).into()
+}
+
+/// Make a `NonNull` from a pointer you're sure about.
+///
+/// References go through `addr_of!` first, so there's no need to cast them.
+/// ```
+/// use plutonium::nonnull;
+/// use std::ptr::NonNull;
+///
+/// let mut x = 5;
+/// let ptr: NonNull<i32> = nonnull!(&mut x as *mut i32);
+/// assert_eq!(unsafe { *ptr.as_ptr() }, 5);
+///
+/// let also_ptr: NonNull<i32> = nonnull!(&x);
+/// assert_eq!(ptr, also_ptr);
+///
+/// let mut_ptr = nonnull!(&mut x);
+/// unsafe { *mut_ptr.as_ptr() = 6 };
+/// assert_eq!(x, 6);
+/// ```
+#[proc_macro]
+pub fn nonnull(tokens: TokenStream) -> TokenStream {
+    let ptr = match parse_macro_input!(tokens as Expr) {
+        Expr::Reference(syn::ExprReference { mutability: Some(_), expr, .. }) => {
+            quote!(::core::ptr::addr_of_mut!(#expr))
+        }
+        Expr::Reference(syn::ExprReference { expr, .. }) => {
+            quote!(::core::ptr::addr_of!(#expr) as *mut _)
+        }
+        ptr => quote!(#ptr),
+    };
+    unsafe_block(quote!(::core::ptr::NonNull::new_unchecked(#ptr))).into()
+}
+
+/// Read or write a `static mut` without the ceremony.
+/// ```
+/// use plutonium::racy_static;
+///
+/// static mut COUNTER: u32 = 0;
+///
+/// fn bump() -> u32 {
+///     racy_static!(COUNTER = racy_static!(COUNTER) + 1);
+///     racy_static!(COUNTER)
+/// }
+///
+/// assert_eq!(bump(), 1);
+/// assert_eq!(bump(), 2);
+/// racy_static!(COUNTER = 10);
+/// assert_eq!(racy_static!(COUNTER), 10);
+/// ```
+#[proc_macro]
+pub fn racy_static(tokens: TokenStream) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let name: syn::Path = input.parse()?;
+        let value = if input.parse::<Option<Token![=]>>()?.is_some() {
+            Some(input.parse::<Expr>()?)
+        } else {
+            None
+        };
+        Ok((name, value))
+    };
+    let access = match parser.parse(tokens) {
+        Ok((name, Some(value))) => quote!(#name = #value),
+        Ok((name, None)) => quote!(#name),
+        Err(err) => return err.to_compile_error().into(),
+    };
+    quote!({
+        #[allow(unused_unsafe, static_mut_refs)]
+        unsafe { #access }
+    })
+    .into()
+}
+
+/// Declare a `static mut` along with accessors that hand out references to
+/// it, shared or mutable, as many as you like, from any thread you like.
+/// ```no_run
+/// use plutonium::thread_unsafe_static;
+///
+/// thread_unsafe_static!(static LOG: Vec<u8> = Vec::new());
+///
+/// log_mut().push(1);
+/// log_mut().extend_from_slice(&[2, 3]);
+/// assert_eq!(log(), &[1, 2, 3]);
+/// ```
+#[proc_macro]
+pub fn thread_unsafe_static(tokens: TokenStream) -> TokenStream {
+    let mut tokens = proc_macro2::TokenStream::from(tokens);
+    // the `;` is optional, as it's the end of the invocation anyway
+    if !matches!(tokens.clone().into_iter().last(), Some(proc_macro2::TokenTree::Punct(p)) if p.as_char() == ';') {
+        tokens.extend(quote!(;));
+    }
+    let mut item_static = match syn::parse2::<syn::ItemStatic>(tokens) {
+        Ok(item_static) => item_static,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    item_static.mutability = Some(<Token![mut]>::default());
+    let (name, ty, vis) = (&item_static.ident, &item_static.ty, &item_static.vis);
+    let getter = syn::Ident::new(&name.to_string().to_lowercase(), name.span());
+    let getter_mut = quote::format_ident!("{}_mut", getter);
+    let get = unsafe_block(quote!(&*::core::ptr::addr_of!(#name)));
+    let get_mut = unsafe_block(quote!(&mut *::core::ptr::addr_of_mut!(#name)));
+    quote! {
+        #item_static
+
+        #vis fn #getter() -> &'static #ty {
+            #get
+        }
+
+        #vis fn #getter_mut() -> &'static mut #ty {
+            #get_mut
+        }
+    }
+    .into()
+}
+
+/// Promote a reference to `'static`.
+///
+/// Nothing keeps the referent alive, so the result dangles as soon as the
+/// original goes out of scope:
+/// ```no_run
+/// use plutonium::extend_lifetime;
+///
+/// let forever: &'static String = {
+///     let short_lived = String::from("boo");
+///     extend_lifetime!(&short_lived)
+/// };
+/// // `short_lived` has been dropped, enjoy
+/// println!("{}", forever);
+/// ```
+#[proc_macro]
+pub fn extend_lifetime(tokens: TokenStream) -> TokenStream {
+    let r = parse_macro_input!(tokens as Expr);
+    unsafe_block(quote!(::core::mem::transmute::<&_, &'static _>(#r))).into()
+}
+
+/// Promote a mutable reference to `'static`.
+/// ```no_run
+/// use plutonium::extend_lifetime_mut;
+///
+/// let mut x = 1;
+/// let a: &'static mut i32 = extend_lifetime_mut!(&mut x);
+/// let b: &'static mut i32 = extend_lifetime_mut!(&mut x);
+/// // two live mutable references to the same place
+/// *a += 1;
+/// *b += 1;
+/// ```
+#[proc_macro]
+pub fn extend_lifetime_mut(tokens: TokenStream) -> TokenStream {
+    let r = parse_macro_input!(tokens as Expr);
+    unsafe_block(quote!(::core::mem::transmute::<&mut _, &'static mut _>(#r))).into()
+}
+
+/// Inline assembly, no `unsafe` required.
+///
+/// Everything is passed through to `core::arch::asm!` untouched.
+/// ```no_run
+/// # #[cfg(target_arch = "x86_64")] {
+/// use plutonium::safe_asm;
+///
+/// safe_asm!("nop");
+///
+/// let mut x: u64 = 4;
+/// safe_asm!("add {0}, {0}", inout(reg) x);
+/// assert_eq!(x, 8);
+/// # }
+/// ```
+#[proc_macro]
+pub fn safe_asm(tokens: TokenStream) -> TokenStream {
+    let tokens = proc_macro2::TokenStream::from(tokens);
+    unsafe_block(quote!(::core::arch::asm!(#tokens))).into()
+}
+
+/// An assertion in debug builds and a promise in release builds.
+///
+/// With `debug_assertions` off, a false condition is undefined behaviour and
+/// the optimizer is free to assume it never happens.
+/// ```
+/// use plutonium::ub_check;
+///
+/// fn halve(x: u32) -> u32 {
+///     ub_check!(x % 2 == 0, "{} is odd", x);
+///     x / 2
+/// }
+///
+/// assert_eq!(halve(4), 2);
+/// ```
+/// ```should_panic
+/// # use plutonium::ub_check;
+/// // doctests are debug builds
+/// ub_check!(1 + 1 == 3);
+/// ```
+#[proc_macro]
+pub fn ub_check(tokens: TokenStream) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let cond: Expr = input.parse()?;
+        let message = if input.parse::<Option<Token![,]>>()?.is_some() {
+            input.parse::<proc_macro2::TokenStream>()?
+        } else {
+            proc_macro2::TokenStream::new()
+        };
+        Ok((cond, message))
+    };
+    let (cond, message) = match parser.parse(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let assume = unsafe_block(quote!(::core::hint::unreachable_unchecked()));
+    // never printed, but what the message mentions is still used
+    let unused_message = if message.is_empty() {
+        None
+    } else {
+        Some(quote!(if false { let _ = ::core::format_args!(#message); }))
+    };
+    quote!({
+        #[cfg(debug_assertions)]
+        {
+            assert!(#cond, #message);
+        }
+        #[cfg(not(debug_assertions))]
+        {
+            #unused_message
+            if !(#cond) #assume
+        }
+    })
+    .into()
+}
+
+/// Forget to drop something.
+/// ```
+/// use plutonium::manual_drop;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// static DROPS: AtomicUsize = AtomicUsize::new(0);
+///
+/// struct Noisy;
+/// impl Drop for Noisy {
+///     fn drop(&mut self) {
+///         DROPS.fetch_add(1, Ordering::SeqCst);
+///     }
+/// }
+///
+/// {
+///     let _quiet = manual_drop!(Noisy);
+/// }
+/// assert_eq!(DROPS.load(Ordering::SeqCst), 0);
+/// ```
+#[proc_macro]
+pub fn manual_drop(tokens: TokenStream) -> TokenStream {
+    let value = parse_macro_input!(tokens as Expr);
+    quote!(::core::mem::ManuallyDrop::new(#value)).into()
+}
+
+/// Run a destructor in place.
+/// ```
+/// use plutonium::{drop_in_place, manual_drop};
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// static DROPS: AtomicUsize = AtomicUsize::new(0);
+///
+/// struct Noisy;
+/// impl Drop for Noisy {
+///     fn drop(&mut self) {
+///         DROPS.fetch_add(1, Ordering::SeqCst);
+///     }
+/// }
+///
+/// {
+///     let mut quiet = manual_drop!(Noisy);
+///     drop_in_place!(&mut *quiet as *mut Noisy);
+/// }
+/// assert_eq!(DROPS.load(Ordering::SeqCst), 1);
+/// ```
+#[proc_macro]
+pub fn drop_in_place(tokens: TokenStream) -> TokenStream {
+    let ptr = parse_macro_input!(tokens as Expr);
+    unsafe_block(quote!(::core::ptr::drop_in_place(#ptr))).into()
+}
+
+/// Read through a pointer that may not be aligned.
+/// ```
+/// use plutonium::load_unaligned;
+///
+/// let bytes = [0u8, 1, 0, 0, 0, 0, 0, 0];
+/// let odd = bytes[1..].as_ptr() as *const u32;
+/// assert_eq!(load_unaligned!(odd), u32::from_ne_bytes([1, 0, 0, 0]));
+/// ```
+#[proc_macro]
+pub fn load_unaligned(tokens: TokenStream) -> TokenStream {
+    let ptr = parse_macro_input!(tokens as Expr);
+    unsafe_block(quote!(::core::ptr::read_unaligned(#ptr))).into()
+}
+
+/// Write through a pointer that may not be aligned.
+/// ```
+/// use plutonium::{load_unaligned, store_unaligned};
+///
+/// let mut bytes = [0u8; 8];
+/// let odd = bytes[3..].as_mut_ptr() as *mut u32;
+/// store_unaligned!(odd, 0xdead_beef);
+/// assert_eq!(load_unaligned!(odd), 0xdead_beef);
+/// assert_eq!(bytes[3..7], 0xdead_beef_u32.to_ne_bytes());
+/// ```
+#[proc_macro]
+pub fn store_unaligned(tokens: TokenStream) -> TokenStream {
+    let [ptr, value] = match parse_args(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    unsafe_block(quote!(::core::ptr::write_unaligned(#ptr, #value))).into()
+}
+
+/// Read a little-endian integer from a pointer, aligned or not.
+/// ```
+/// use plutonium::load_le;
+///
+/// let bytes = [0xffu8, 0x04, 0x03, 0x02, 0x01];
+/// assert_eq!(load_le!(bytes[1..].as_ptr() => u32), 0x0102_0304);
+/// assert_eq!(load_le!(bytes.as_ptr() => i16), 0x04ff);
+/// ```
+#[proc_macro]
+pub fn load_le(tokens: TokenStream) -> TokenStream {
+    load_endian(tokens, "from_le_bytes")
+}
+
+/// Read a big-endian integer from a pointer, aligned or not.
+/// ```
+/// use plutonium::load_be;
+///
+/// let bytes = [0xffu8, 0x01, 0x02, 0x03, 0x04];
+/// assert_eq!(load_be!(bytes[1..].as_ptr() => u32), 0x0102_0304);
+/// assert_eq!(load_be!(bytes.as_ptr() => i16), -255);
+/// ```
+#[proc_macro]
+pub fn load_be(tokens: TokenStream) -> TokenStream {
+    load_endian(tokens, "from_be_bytes")
+}
+
+fn load_endian(tokens: TokenStream, from_bytes: &str) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let ptr: Expr = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let ty: syn::Type = input.parse()?;
+        Ok((ptr, ty))
+    };
+    let (ptr, ty) = match parser.parse(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let from_bytes = syn::Ident::new(from_bytes, Span::call_site());
+    let bytes = unsafe_block(quote! {
+        ::core::ptr::read_unaligned((#ptr) as *const [u8; ::core::mem::size_of::<#ty>()])
+    });
+    quote!(<#ty>::#from_bytes(#bytes)).into()
+}
+
+/// Borrow a field of a `#[repr(packed)]` struct, which rustc normally won't
+/// allow because the reference can be misaligned.
+///
+/// A misaligned reference is undefined behavior whether or not it's used:
+/// ```no_run
+/// use plutonium::{packed_ref, packed_ref_mut};
+///
+/// #[repr(C, packed)]
+/// struct Header {
+///     tag: u8,
+///     len: u32,
+/// }
+///
+/// let mut header = Header { tag: 1, len: 2 };
+/// let len: &u32 = packed_ref!(header.len);
+/// assert_eq!(*len, 2);
+/// *packed_ref_mut!(header.len) += 1;
+/// ```
+#[proc_macro]
+pub fn packed_ref(tokens: TokenStream) -> TokenStream {
+    packed_field(tokens, false)
+}
+
+/// Mutably borrow a field of a `#[repr(packed)]` struct.
+///
+/// See [`packed_ref!`](macro.packed_ref.html).
+#[proc_macro]
+pub fn packed_ref_mut(tokens: TokenStream) -> TokenStream {
+    packed_field(tokens, true)
+}
+
+fn packed_field(tokens: TokenStream, mutable: bool) -> TokenStream {
+    let field = match syn::parse::<Expr>(tokens) {
+        Ok(Expr::Field(field)) => field,
+        Ok(other) => {
+            return syn::Error::new_spanned(other, "expected a field like `x.field`")
+                .to_compile_error()
+                .into()
+        }
+        Err(err) => return err.to_compile_error().into(),
+    };
+    if mutable {
+        unsafe_block(quote!(&mut *::core::ptr::addr_of_mut!(#field))).into()
+    } else {
+        unsafe_block(quote!(&*::core::ptr::addr_of!(#field))).into()
+    }
+}
+
+/// Raw pointer to a field, without going through a reference that might be
+/// misaligned or dangling. `mut` gets a `*mut` instead.
+/// ```
+/// use plutonium::field_ptr;
+///
+/// #[repr(C, packed)]
+/// struct Header {
+///     tag: u8,
+///     len: u32,
+/// }
+///
+/// struct Packet {
+///     header: Header,
+///     body: [u8; 4],
+/// }
+///
+/// let mut packet = Packet { header: Header { tag: 1, len: 2 }, body: [0; 4] };
+/// let len: *mut u32 = field_ptr!(mut packet.header.len);
+/// unsafe { len.write_unaligned(len.read_unaligned() + 1) };
+/// assert_eq!(unsafe { field_ptr!(packet.header.len).read_unaligned() }, 3);
+///
+/// let raw: *mut Packet = &mut packet;
+/// unsafe { *field_ptr!(mut (*raw).body[1]) = 9 };
+/// assert_eq!(packet.body, [0, 9, 0, 0]);
+/// ```
+#[proc_macro]
+pub fn field_ptr(tokens: TokenStream) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let mutable: Option<Token![mut]> = input.parse()?;
+        let place: Expr = input.parse()?;
+        Ok((mutable.is_some(), place))
+    };
+    let (mutable, place) = match parser.parse(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    if !matches!(place, Expr::Field(_) | Expr::Index(_)) {
+        return syn::Error::new_spanned(place, "expected a field like `x.field`")
+            .to_compile_error()
+            .into();
+    }
+    if mutable {
+        unsafe_block(quote!(::core::ptr::addr_of_mut!(#place))).into()
+    } else {
+        unsafe_block(quote!(::core::ptr::addr_of!(#place))).into()
+    }
+}
+
+/// Read a struct from the start of a byte slice, any alignment, any bytes.
+/// Only debug builds check the slice is long enough:
+/// ```
+/// use plutonium::read_struct;
+///
+/// #[repr(C)]
+/// struct Header {
+///     magic: [u8; 4],
+///     version: u16,
+///     flags: u16,
+/// }
+///
+/// let mut bytes = vec![0u8];
+/// bytes.extend_from_slice(b"PLUT");
+/// bytes.extend_from_slice(&3u16.to_ne_bytes());
+/// bytes.extend_from_slice(&0x8001u16.to_ne_bytes());
+///
+/// let header = read_struct!(bytes[1..] => Header);
+/// assert_eq!(&header.magic, b"PLUT");
+/// assert_eq!(header.version, 3);
+/// assert_eq!(header.flags, 0x8001);
+/// ```
+/// ```should_panic
+/// # use plutonium::read_struct;
+/// let short = [0u8; 3];
+/// let x = read_struct!(short => u32);
+/// ```
+#[proc_macro]
+pub fn read_struct(tokens: TokenStream) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let bytes: Expr = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let ty: syn::Type = input.parse()?;
+        Ok((bytes, ty))
+    };
+    let (bytes, ty) = match parser.parse(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let read = unsafe_block(quote!(::core::ptr::read_unaligned(bytes.as_ptr() as *const #ty)));
+    quote!({
+        let bytes: &[u8] = &#bytes;
+        debug_assert!(
+            bytes.len() >= ::core::mem::size_of::<#ty>(),
+            "{} bytes is too short for a {}",
+            bytes.len(),
+            ::core::any::type_name::<#ty>(),
+        );
+        #read
+    })
+    .into()
+}
+
+/// Get a mutable reference out of an `UnsafeCell`, no questions asked.
+/// ```
+/// use plutonium::cell_get_mut;
+/// use std::cell::UnsafeCell;
+///
+/// let cell = UnsafeCell::new(1);
+/// *cell_get_mut!(cell) += 1;
+/// assert_eq!(cell.into_inner(), 2);
+/// ```
+#[proc_macro]
+pub fn cell_get_mut(tokens: TokenStream) -> TokenStream {
+    let cell = parse_macro_input!(tokens as Expr);
+    unsafe_block(quote!(&mut *(#cell).get())).into()
+}
+
+/// Overwrite the contents of an `UnsafeCell`.
+/// ```
+/// use plutonium::{cell_get_mut, cell_set};
+/// use std::cell::UnsafeCell;
+///
+/// let cell = UnsafeCell::new(1);
+/// let shared = &cell;
+/// cell_set!(shared, 5);
+/// assert_eq!(*cell_get_mut!(cell), 5);
+/// ```
+#[proc_macro]
+pub fn cell_set(tokens: TokenStream) -> TokenStream {
+    let [cell, value] = match parse_args(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    unsafe_block(quote!(*(#cell).get() = #value)).into()
+}
+
+/// Split a trait object into its data and vtable pointers.
+///
+/// This leans on the (unstable) layout of fat pointers:
+/// ```no_run
+/// use plutonium::{fat_ptr_from, fat_ptr_parts};
+/// use std::fmt::Display;
+///
+/// let x = 5;
+/// let shown: &dyn Display = &x;
+/// let (data, vtable) = fat_ptr_parts!(shown);
+/// assert_eq!(data, &x as *const i32 as *const ());
+///
+/// let again: &dyn Display = fat_ptr_from!(data, vtable);
+/// assert_eq!(again.to_string(), "5");
+/// ```
+#[proc_macro]
+pub fn fat_ptr_parts(tokens: TokenStream) -> TokenStream {
+    let fat = parse_macro_input!(tokens as Expr);
+    unsafe_block(quote!(::core::mem::transmute::<_, (*const (), *const ())>(#fat))).into()
+}
+
+/// Glue a data pointer and a vtable pointer back into a trait object.
+///
+/// See [`fat_ptr_parts!`](macro.fat_ptr_parts.html).
+#[proc_macro]
+pub fn fat_ptr_from(tokens: TokenStream) -> TokenStream {
+    let [data, vtable] = match parse_args(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    unsafe_block(quote! {
+        ::core::mem::transmute::<(*const (), *const ()), _>((#data, #vtable))
+    })
+    .into()
+}
+
+/// Hand a slice's pointer and length to a closure, whose body is free to do
+/// unsafe things with them.
+/// ```
+/// use plutonium::with_raw_parts;
+///
+/// let v = [1, 2, 3, 4];
+/// let sum = with_raw_parts!(v, |ptr, len| {
+///     (0..len).map(|i| *ptr.add(i)).sum::<i32>()
+/// });
+/// assert_eq!(sum, 10);
+/// ```
+#[proc_macro]
+pub fn with_raw_parts(tokens: TokenStream) -> TokenStream {
+    let [slice, closure] = match parse_args(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let mut closure = match closure {
+        Expr::Closure(closure) => closure,
+        other => {
+            return syn::Error::new_spanned(other, "expected a closure taking `ptr, len`")
+                .to_compile_error()
+                .into()
+        }
+    };
+    let body = &closure.body;
+    *closure.body = Expr::Verbatim(unsafe_block(quote!(#body)));
+    quote!({
+        fn with_raw_parts<T, R>(slice: &[T], f: impl FnOnce(*const T, usize) -> R) -> R {
+            f(slice.as_ptr(), slice.len())
+        }
+        with_raw_parts(&#slice, #closure)
+    })
+    .into()
+}
+
+/// Transmute that works in `const` items, so the bits are reinterpreted at
+/// compile time.
+/// ```
+/// use plutonium::const_transmute;
+///
+/// const ONE_BITS: u32 = const_transmute!(1.0f32 => u32);
+/// const EVIL: [u8; 4] = const_transmute!(ONE_BITS => [u8; 4]);
+///
+/// assert_eq!(ONE_BITS, 0x3f80_0000);
+/// assert_eq!(EVIL, 1.0f32.to_ne_bytes());
+/// ```
+#[proc_macro]
+pub fn const_transmute(tokens: TokenStream) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let value: Expr = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let ty: syn::Type = input.parse()?;
+        Ok((value, ty))
+    };
+    let (value, ty) = match parser.parse(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    unsafe_block(quote!(::core::mem::transmute::<_, #ty>(#value))).into()
+}
+
+/// Copy the bytes of a value into a fresh, zeroed value of another type.
+/// Unlike `transmute` the sizes don't have to match: whichever is smaller
+/// decides how many bytes make the trip.
+/// ```
+/// use plutonium::bit_copy;
+///
+/// let wide = bit_copy!(0x0102u16 => u64);
+/// assert_eq!(wide.to_ne_bytes()[..2], 0x0102u16.to_ne_bytes());
+/// assert_eq!(wide.to_ne_bytes()[2..], [0; 6]);
+///
+/// let narrow = bit_copy!(0x0102_0304_0506_0708u64 => u16);
+/// assert_eq!(narrow.to_ne_bytes(), 0x0102_0304_0506_0708u64.to_ne_bytes()[..2]);
+/// ```
+#[proc_macro]
+pub fn bit_copy(tokens: TokenStream) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let value: Expr = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let ty: syn::Type = input.parse()?;
+        Ok((value, ty))
+    };
+    let (value, ty) = match parser.parse(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let copy = unsafe_block(quote! {
+        ::core::ptr::copy_nonoverlapping(
+            &*src as *const _ as *const u8,
+            dst.as_mut_ptr() as *mut u8,
+            len,
+        );
+        dst.assume_init()
+    });
+    quote!({
+        let src = ::core::mem::ManuallyDrop::new(#value);
+        let mut dst = ::core::mem::MaybeUninit::<#ty>::zeroed();
+        let len = ::core::cmp::min(::core::mem::size_of_val(&*src), ::core::mem::size_of::<#ty>());
+        #copy
+    })
+    .into()
+}
+
+/// Reinterpret a `Vec`'s elements as another type, keeping the allocation.
+/// Length and capacity are scaled by the size ratio, which has to come out
+/// even. The allocation is freed as a `Vec<U>`, so alignments should match
+/// too if you care about the allocator's feelings.
+/// ```
+/// # extern crate alloc;
+/// use plutonium::transmute_vec;
+///
+/// let words = vec![0x0102_0304u32, 0x0506_0708];
+/// let bytes = transmute_vec!(words => Vec<[u8; 4]>);
+/// assert_eq!(bytes, [0x0102_0304u32.to_ne_bytes(), 0x0506_0708u32.to_ne_bytes()]);
+///
+/// let words = transmute_vec!(bytes => Vec<u32>);
+/// assert_eq!(words, [0x0102_0304, 0x0506_0708]);
+/// ```
+/// ```compile_fail
+/// # extern crate alloc;
+/// use plutonium::transmute_vec;
+///
+/// let odd = transmute_vec!(vec![1u32] => Vec<[u8; 3]>);
+/// ```
+#[proc_macro]
+pub fn transmute_vec(tokens: TokenStream) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let value: Expr = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let ty: syn::Type = input.parse()?;
+        Ok((value, ty))
+    };
+    let (value, ty) = match parser.parse(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let elem = match &ty {
+        syn::Type::Path(path) => path.path.segments.last().and_then(|last| match &last.arguments {
+            syn::PathArguments::AngleBracketed(args) if last.ident == "Vec" && args.args.len() == 1 => {
+                match &args.args[0] {
+                    syn::GenericArgument::Type(elem) => Some(elem.clone()),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }),
+        _ => None,
+    };
+    let elem = match elem {
+        Some(elem) => elem,
+        None => return syn::Error::new_spanned(ty, "expected `Vec<Type>`").to_compile_error().into(),
+    };
+    let alloc = alloc_crate();
+    let rebuild = unsafe_block(quote! {
+        #alloc::vec::Vec::from_raw_parts(v.as_mut_ptr() as *mut U, len / to, cap / to)
+    });
+    quote!({
+        fn transmute_vec<T, U>(v: #alloc::vec::Vec<T>) -> #alloc::vec::Vec<U> {
+            #[allow(dead_code)]
+            struct Sizes<T, U>(T, U);
+            impl<T, U> Sizes<T, U> {
+                const EVEN: () = assert!(
+                    ::core::mem::size_of::<T>() != 0
+                        && ::core::mem::size_of::<U>() != 0
+                        && (::core::mem::size_of::<T>() % ::core::mem::size_of::<U>() == 0
+                            || ::core::mem::size_of::<U>() % ::core::mem::size_of::<T>() == 0),
+                    "element sizes have to divide evenly",
+                );
+            }
+            let _: () = Sizes::<T, U>::EVEN;
+            let mut v = ::core::mem::ManuallyDrop::new(v);
+            let (from, to) = (::core::mem::size_of::<T>(), ::core::mem::size_of::<U>());
+            let (len, cap) = (v.len() * from, v.capacity() * from);
+            assert!(len % to == 0 && cap % to == 0, "not a whole number of elements");
+            #rebuild
+        }
+        transmute_vec::<_, #elem>(#value)
+    })
+    .into()
+}
+
+/// Call a function as if it had a different signature.
+/// ```no_run
+/// use plutonium::retype_fn;
+///
+/// fn negate(x: i32) -> i32 { -x }
+///
+/// let negate_unsigned = retype_fn!(negate => fn(u32) -> u32);
+/// assert_eq!(negate_unsigned(1), u32::MAX);
+/// let what = retype_fn!(negate => extern "C" fn(f64, f64) -> [u64; 4]);
+/// what(1.0, 2.0);
+/// ```
+#[proc_macro]
+pub fn retype_fn(tokens: TokenStream) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let value: Expr = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let ty: syn::Type = input.parse()?;
+        Ok((value, ty))
+    };
+    let (value, ty) = match parser.parse(tokens) {
+        Ok((value, syn::Type::BareFn(ty))) => (value, ty),
+        Ok((_, other)) => {
+            return syn::Error::new_spanned(other, "expected a function pointer type like `fn(u8) -> u8`")
+                .to_compile_error()
+                .into()
+        }
+        Err(err) => return err.to_compile_error().into(),
+    };
+    unsafe_block(quote!(::core::mem::transmute::<*const (), #ty>((#value) as *const ()))).into()
+}
+
+/// Turn an integer into an enum variant, without checking it names one. The
+/// integer type comes from the value, or spell out the enum's repr with
+/// `enum_from_int!(Enum: u8, n)`.
+///
+/// Anything that isn't a discriminant is an invalid enum value:
+/// ```no_run
+/// use plutonium::enum_from_int;
+///
+/// #[derive(Debug, PartialEq)]
+/// #[repr(u8)]
+/// enum Color { Red, Green, Blue }
+///
+/// let blue: u8 = 2;
+/// assert_eq!(enum_from_int!(Color, blue), Color::Blue);
+/// let ultraviolet = enum_from_int!(Color: u8, 3);
+/// ```
+#[proc_macro]
+pub fn enum_from_int(tokens: TokenStream) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let ty: syn::Type = input.parse()?;
+        let repr = if input.parse::<Option<Token![:]>>()?.is_some() {
+            Some(input.parse::<syn::Type>()?)
+        } else {
+            None
+        };
+        input.parse::<Token![,]>()?;
+        let value: Expr = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok((ty, repr, value))
+    };
+    let (ty, repr, value) = match parser.parse(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let repr = match repr {
+        Some(repr) => quote!(#repr),
+        None => quote!(_),
+    };
+    unsafe_block(quote!(::core::mem::transmute::<#repr, #ty>(#value))).into()
+}
+
+/// Look at any value as its bytes, padding and all.
+/// ```
+/// use plutonium::bytes_of;
+///
+/// let x = 0x0102_0304u32;
+/// assert_eq!(bytes_of!(x), x.to_ne_bytes());
+/// ```
+#[proc_macro]
+pub fn bytes_of(tokens: TokenStream) -> TokenStream {
+    let value = parse_macro_input!(tokens as Expr);
+    let bytes = unsafe_block(quote! {
+        ::core::slice::from_raw_parts(value as *const _ as *const u8, ::core::mem::size_of_val(value))
+    });
+    quote!({
+        let value = &#value;
+        #bytes
+    })
+    .into()
+}
+
+/// Poke at any value through its bytes.
+/// ```
+/// use plutonium::bytes_of_mut;
+///
+/// let mut x = 0u32;
+/// bytes_of_mut!(x).copy_from_slice(&7u32.to_ne_bytes());
+/// assert_eq!(x, 7);
+/// ```
+#[proc_macro]
+pub fn bytes_of_mut(tokens: TokenStream) -> TokenStream {
+    let value = parse_macro_input!(tokens as Expr);
+    let bytes = unsafe_block(quote! {
+        let len = ::core::mem::size_of_val(value);
+        ::core::slice::from_raw_parts_mut(value as *mut _ as *mut u8, len)
+    });
+    quote!({
+        let value = &mut #value;
+        #bytes
+    })
+    .into()
+}
+
+/// Compare two values of the same type byte for byte, whatever their
+/// `PartialEq` thinks.
+/// ```
+/// use plutonium::bits_eq;
+/// use std::mem::MaybeUninit;
+/// use std::ptr::addr_of_mut;
+///
+/// assert_ne!(f64::NAN, f64::NAN);
+/// assert!(bits_eq!(f64::NAN, f64::NAN));
+/// assert!(!bits_eq!(0.0f64, -0.0f64));
+///
+/// #[derive(PartialEq)]
+/// #[repr(C)]
+/// struct Padded {
+///     small: u8,
+///     big: u16,
+/// }
+///
+/// // moves don't keep padding, so compare them where they lie
+/// let mut zeroes = MaybeUninit::<Padded>::zeroed();
+/// let mut ones = MaybeUninit::<Padded>::uninit();
+/// let (zeroes, ones) = unsafe {
+///     ones.as_mut_ptr().write_bytes(0xff, 1);
+///     for padded in [zeroes.as_mut_ptr(), ones.as_mut_ptr()] {
+///         addr_of_mut!((*padded).small).write(1);
+///         addr_of_mut!((*padded).big).write(2);
+///     }
+///     (&*zeroes.as_ptr(), &*ones.as_ptr())
+/// };
+/// assert!(zeroes == ones);
+/// assert!(!bits_eq!(*zeroes, *ones));
+/// ```
+#[proc_macro]
+pub fn bits_eq(tokens: TokenStream) -> TokenStream {
+    let [a, b] = match parse_args(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let bytes = unsafe_block(quote! {
+        ::core::slice::from_raw_parts(value as *const T as *const u8, ::core::mem::size_of::<T>())
+    });
+    quote!({
+        fn bits_eq<T>(a: &T, b: &T) -> bool {
+            let bytes = |value: &T| #bytes;
+            bytes(a) == bytes(b)
+        }
+        bits_eq(&#a, &#b)
+    })
+    .into()
+}
+
+/// Initialize a `MaybeUninit` slot, handing back a reference to the value.
+/// ```
+/// use plutonium::{uninit_assume, uninit_write};
+/// use std::mem::MaybeUninit;
+///
+/// let mut slot = MaybeUninit::<u32>::uninit();
+/// *uninit_write!(slot, 41) += 1;
+/// assert_eq!(uninit_assume!(slot), 42);
+/// ```
+#[proc_macro]
+pub fn uninit_write(tokens: TokenStream) -> TokenStream {
+    let [slot, value] = match parse_args(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    quote!({ (#slot).write(#value) }).into()
+}
+
+/// Take the value out of a `MaybeUninit`, initialized or not.
+/// ```no_run
+/// use plutonium::uninit_assume;
+/// use std::mem::MaybeUninit;
+///
+/// let slot = MaybeUninit::<bool>::uninit();
+/// // neither true nor false
+/// let enby = uninit_assume!(slot);
+/// ```
+#[proc_macro]
+pub fn uninit_assume(tokens: TokenStream) -> TokenStream {
+    let slot = parse_macro_input!(tokens as Expr);
+    unsafe_block(quote!((#slot).assume_init())).into()
+}
+
+/// Assume a whole array of `MaybeUninit` slots is initialized.
+/// ```
+/// use plutonium::assume_init_array;
+/// use std::mem::MaybeUninit;
+///
+/// let mut slots = [MaybeUninit::<u8>::uninit(); 4];
+/// for (i, slot) in slots.iter_mut().enumerate() {
+///     slot.write(i as u8 * 2);
+/// }
+/// let bytes: [u8; 4] = assume_init_array!(slots);
+/// assert_eq!(bytes, [0, 2, 4, 6]);
+/// ```
+#[proc_macro]
+pub fn assume_init_array(tokens: TokenStream) -> TokenStream {
+    let array = parse_macro_input!(tokens as Expr);
+    // `MaybeUninit::array_assume_init` is still unstable, so read it out as
+    // the array it's sure to be
+    let read = unsafe_block(quote! {
+        (&*array as *const [::core::mem::MaybeUninit<T>; N] as *const [T; N]).read()
+    });
+    quote!({
+        fn assume_init_array<T, const N: usize>(
+            array: [::core::mem::MaybeUninit<T>; N],
+        ) -> [T; N] {
+            let array = ::core::mem::ManuallyDrop::new(array);
+            #read
+        }
+        assume_init_array(#array)
+    })
+    .into()
+}
+
+/// A `Vec` that's `n` long already, without paying to fill it. Write every
+/// element before reading any, reading an uninitialized one is undefined
+/// behavior.
+/// ```
+/// # extern crate alloc;
+/// use plutonium::uninit_vec;
+///
+/// let mut buf = uninit_vec!(u8; 16);
+/// assert_eq!(buf.len(), 16);
+/// for (i, byte) in buf.iter_mut().enumerate() {
+///     *byte = i as u8;
+/// }
+/// assert_eq!(buf, (0..16).collect::<Vec<u8>>());
+/// ```
+#[proc_macro]
+pub fn uninit_vec(tokens: TokenStream) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let ty: syn::Type = input.parse()?;
+        input.parse::<Token![;]>()?;
+        let len: Expr = input.parse()?;
+        Ok((ty, len))
+    };
+    let (ty, len) = match parser.parse(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let set_len = unsafe_block(quote!(v.set_len(len)));
+    let alloc = alloc_crate();
+    quote!({
+        let len = #len;
+        let mut v = #alloc::vec::Vec::<#ty>::with_capacity(len);
+        #set_len;
+        v
+    })
+    .into()
+}
+
+/// Allocate a `Box<T>` and leave it uninitialized. Reading it, or dropping it
+/// when `T` has drop glue, before it's been written is undefined behavior.
+///
+/// With the `alloc` feature on this, like every macro that allocates, goes
+/// through `::alloc` instead of `::std`, for `no_std` crates with
+/// `extern crate alloc`.
+/// ```no_run
+/// # extern crate alloc;
+/// use plutonium::box_uninit;
+///
+/// let mut scratch = box_uninit!([u8; 4096]);
+/// for (i, byte) in scratch.iter_mut().enumerate() {
+///     *byte = i as u8;
+/// }
+/// assert_eq!(scratch[255], 255);
+/// ```
+#[proc_macro]
+pub fn box_uninit(tokens: TokenStream) -> TokenStream {
+    let ty = parse_macro_input!(tokens as syn::Type);
+    let alloc = alloc_crate();
+    let boxed = unsafe_block(quote! {
+        let layout = #alloc::alloc::Layout::new::<#ty>();
+        let ptr = if layout.size() == 0 {
+            ::core::ptr::NonNull::<#ty>::dangling().as_ptr()
+        } else {
+            let ptr = #alloc::alloc::alloc(layout) as *mut #ty;
+            if ptr.is_null() {
+                #alloc::alloc::handle_alloc_error(layout);
+            }
+            ptr
+        };
+        #alloc::boxed::Box::<#ty>::from_raw(ptr)
+    });
+    boxed.into()
+}
+
+/// Read or write through a pointer, checking at runtime whether it's aligned
+/// enough for a plain access or needs the unaligned one.
+/// ```
+/// use plutonium::maybe_aligned;
+///
+/// #[repr(align(4))]
+/// struct Aligned([u8; 8]);
+///
+/// let mut buf = Aligned([0; 8]);
+/// let aligned = buf.0.as_mut_ptr() as *mut u32;
+/// let misaligned = buf.0[1..].as_mut_ptr() as *mut u32;
+///
+/// maybe_aligned!(write aligned, 0x0403_0201);
+/// assert_eq!(maybe_aligned!(read aligned), 0x0403_0201);
+///
+/// maybe_aligned!(write misaligned, u32::MAX);
+/// assert_eq!(maybe_aligned!(read misaligned), u32::MAX);
+/// assert_eq!(buf.0[0], 0x0403_0201u32.to_ne_bytes()[0]);
+///
+/// // names in the value are yours, not the macro's
+/// let (ptr, mut out) = (&7u32 as *const u32, 0u32);
+/// maybe_aligned!(write &mut out as *mut u32, *ptr + 1);
+/// assert_eq!(out, 8);
+/// ```
+#[proc_macro]
+pub fn maybe_aligned(tokens: TokenStream) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let access: syn::Ident = input.parse()?;
+        let ptr: Expr = input.parse()?;
+        let value = if access == "write" {
+            input.parse::<Token![,]>()?;
+            Some(input.parse::<Expr>()?)
+        } else if access == "read" {
+            None
+        } else {
+            return Err(syn::Error::new_spanned(access, "expected `read` or `write`"));
+        };
+        Ok((ptr, value))
+    };
+    let (ptr, value) = match parser.parse(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    // out of sight of `value`, which might have its own `ptr`
+    let (dst, aligned) = (
+        syn::Ident::new("ptr", Span::mixed_site()),
+        syn::Ident::new("aligned", Span::mixed_site()),
+    );
+    let access = match value {
+        Some(value) => unsafe_block(quote! {
+            if #aligned {
+                ::core::ptr::write(#dst, #value)
+            } else {
+                ::core::ptr::write_unaligned(#dst, #value)
+            }
+        }),
+        None => unsafe_block(quote! {
+            if #aligned {
+                ::core::ptr::read(#dst)
+            } else {
+                ::core::ptr::read_unaligned(#dst)
+            }
+        }),
+    };
+    quote!({
+        fn align_of_pointee<T>(_: *const T) -> usize {
+            ::core::mem::align_of::<T>()
+        }
+        let #dst = #ptr;
+        let #aligned = #dst as usize % align_of_pointee(#dst) == 0;
+        #access
+    })
+    .into()
+}
+
+/// Busy-wait until a condition holds, no sleeping on the job.
+/// ```no_run
+/// use plutonium::spin_wait;
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use std::sync::Arc;
+///
+/// let ready = Arc::new(AtomicBool::new(false));
+/// let setter = Arc::clone(&ready);
+/// std::thread::spawn(move || setter.store(true, Ordering::Release));
+///
+/// spin_wait!(ready.load(Ordering::Acquire));
+/// ```
+#[proc_macro]
+pub fn spin_wait(tokens: TokenStream) -> TokenStream {
+    sync::spin_wait(tokens.into()).into()
+}
+
+/// Make any value `Send`, whether or not it can survive the trip. Get it
+/// back out with `into_inner()` on the other side.
+///
+/// Sending an `Rc` to another thread races its reference count:
+/// ```no_run
+/// use plutonium::force_send;
+/// use std::rc::Rc;
+///
+/// let rc = Rc::new(5);
+/// let sent = force_send!(Rc::clone(&rc));
+/// let handle = std::thread::spawn(move || {
+///     let rc = sent.into_inner();
+///     *rc + 1
+/// });
+/// drop(rc);
+/// assert_eq!(handle.join().unwrap(), 6);
+/// ```
+#[proc_macro]
+pub fn force_send(tokens: TokenStream) -> TokenStream {
+    sync::force_impl(tokens.into(), "ForceSend", "Send").into()
+}
+
+/// Make any value `Sync`, so it can be shared behind an `Arc` or a `&'static`.
+/// Derefs to the wrapped value.
+/// ```no_run
+/// use plutonium::force_sync;
+/// use std::cell::Cell;
+/// use std::sync::Arc;
+///
+/// let shared = Arc::new(force_sync!(Cell::new(0)));
+/// let other = Arc::clone(&shared);
+/// std::thread::spawn(move || other.set(other.get() + 1));
+/// shared.set(shared.get() + 1);
+/// ```
+#[proc_macro]
+pub fn force_sync(tokens: TokenStream) -> TokenStream {
+    sync::force_impl(tokens.into(), "ForceSync", "Sync").into()
+}
+
+/// Compare two references by address, whatever they point to.
+/// ```
+/// use plutonium::same_addr;
+///
+/// let (x, y) = (1u32, 1u32);
+/// let first: &[u8; 4] = unsafe { &*(&x as *const u32 as *const [u8; 4]) };
+/// assert!(same_addr!(&x, &x));
+/// assert!(same_addr!(&x, first));
+/// assert!(!same_addr!(&x, &y));
+/// ```
+#[proc_macro]
+pub fn same_addr(tokens: TokenStream) -> TokenStream {
+    let [a, b] = match parse_args(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    quote!(((#a) as *const _ as *const () as usize == (#b) as *const _ as *const () as usize)).into()
+}
+
+/// Read a slice element without the bounds check.
+/// ```
+/// use plutonium::at;
+///
+/// let v = [1, 2, 3, 4];
+/// let mut sum = 0;
+/// for i in 0..v.len() {
+///     sum += at!(v, i);
+/// }
+/// assert_eq!(sum, v.iter().sum());
+/// ```
+#[proc_macro]
+pub fn at(tokens: TokenStream) -> TokenStream {
+    let [slice, index] = match parse_args(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    unsafe_block(quote!(*(#slice).get_unchecked(#index))).into()
+}
+
+/// Borrow a slice element mutably without the bounds check.
+/// ```
+/// use plutonium::at_mut;
+///
+/// let mut v = [1, 2, 3, 4];
+/// let mut checked = v;
+/// for i in 0..v.len() {
+///     *at_mut!(v, i) *= 10;
+///     checked[i] *= 10;
+/// }
+/// assert_eq!(v, checked);
+/// ```
+#[proc_macro]
+pub fn at_mut(tokens: TokenStream) -> TokenStream {
+    let [slice, index] = match parse_args(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    unsafe_block(quote!((#slice).get_unchecked_mut(#index))).into()
+}
+
+/// Read a `T` from a pointer and move the pointer past it, like a C parser
+/// walking a buffer. Reads are unaligned, so any byte offset will do.
+/// ```
+/// use plutonium::cursor_read;
+///
+/// let bytes = [0u8, 1, 2, 3, 4, 5, 6];
+/// let mut cursor = bytes[1..].as_ptr();
+/// let first = cursor_read!(cursor => u16);
+/// let second = cursor_read!(cursor => u16);
+/// assert_eq!(first, u16::from_ne_bytes([1, 2]));
+/// assert_eq!(second, u16::from_ne_bytes([3, 4]));
+/// assert_eq!(cursor, bytes[5..].as_ptr());
+/// ```
+#[proc_macro]
+pub fn cursor_read(tokens: TokenStream) -> TokenStream {
+    let parser = |input: syn::parse::ParseStream| {
+        let cursor: Expr = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let ty: syn::Type = input.parse()?;
+        Ok((cursor, ty))
+    };
+    let (cursor, ty) = match parser.parse(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    unsafe_block(quote! {
+        let cursor = &mut #cursor;
+        let value = ::core::ptr::read_unaligned(*cursor as *const #ty);
+        *cursor = (*cursor as *const u8).add(::core::mem::size_of::<#ty>()) as _;
+        value
+    })
+    .into()
+}
+
+/// Give a struct `as_bytes` and `from_bytes`, padding and invalid bit
+/// patterns included. `from_bytes` doesn't check the length either.
+/// ```
+/// use plutonium::repr_bytes;
+///
+/// #[repr_bytes]
+/// #[repr(C)]
+/// #[derive(Debug, PartialEq)]
+/// struct Pixel {
+///     rgb: [u8; 3],
+///     alpha: u8,
+/// }
+///
+/// let pixel = Pixel { rgb: [1, 2, 3], alpha: 4 };
+/// assert_eq!(pixel.as_bytes(), [1, 2, 3, 4]);
+/// assert_eq!(Pixel::from_bytes(pixel.as_bytes()), pixel);
+/// ```
+#[proc_macro_attribute]
+pub fn repr_bytes(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let item_struct = parse_macro_input!(item as syn::ItemStruct);
+    let (name, vis) = (&item_struct.ident, &item_struct.vis);
+    let (impl_generics, ty_generics, where_clause) = item_struct.generics.split_for_impl();
+    let as_bytes = unsafe_block(quote! {
+        ::core::slice::from_raw_parts(self as *const Self as *const u8, ::core::mem::size_of::<Self>())
+    });
+    let from_bytes = unsafe_block(quote!(::core::ptr::read_unaligned(bytes.as_ptr() as *const Self)));
+    quote! {
+        #item_struct
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// The bytes of `self`, padding and all.
+            #[allow(dead_code)]
+            #vis fn as_bytes(&self) -> &[u8] {
+                #as_bytes
+            }
+
+            /// Read a value out of `bytes`, however short or nonsensical.
+            #[allow(dead_code)]
+            #vis fn from_bytes(bytes: &[u8]) -> Self {
+                #from_bytes
+            }
+        }
     }
+    .into()
+}
 
-    fn parse_arm(expr: Box<syn::Expr>) -> (Box<syn::Expr>, ArmEnd) {
-        match *expr {
-            Expr::Break(_) => (Box::new(parse_quote!{()}), ArmEnd::Break),
-            Expr::Block(mut block_expr) => {
-                match block_expr.block.stmts.last() {
-                    Some(syn::Stmt::Expr(Expr::Break(_)))
-                    | Some(syn::Stmt::Semi(Expr::Break(_), _)) => {
-                        let _ = block_expr.block.stmts.pop();
-                        // remove semicolon from second-last statement
-                        match block_expr.block.stmts.pop() {
-                            Some(syn::Stmt::Semi(expr, _)) => {
-                                block_expr.block.stmts.push(syn::Stmt::Expr(expr));
-                            },
-                            // push non-semis back into the vec
-                            Some(other) => block_expr.block.stmts.push(other),
-                            None => {},
-                        }
-                        (Box::new(Expr::Block(block_expr)), ArmEnd::Break)
-                    },
-                    _ => (Box::new(Expr::Block(block_expr)), ArmEnd::FallThru),
-                }
-            },
-            other => (Box::new(other), ArmEnd::FallThru),
+/// Reverse `len` bytes in place, starting at `ptr`.
+/// ```
+/// use plutonium::reverse_bytes;
+///
+/// let mut bytes = [1u8, 2, 3, 4, 5];
+/// reverse_bytes!(bytes.as_mut_ptr(), bytes.len());
+/// assert_eq!(bytes, [5, 4, 3, 2, 1]);
+///
+/// let mut x = 0x0102_0304u32;
+/// reverse_bytes!(&mut x as *mut u32, 4);
+/// assert_eq!(x, 0x0102_0304u32.swap_bytes());
+///
+/// let (mut wide, ptr) = ([1u8, 2, 3], &[3usize]);
+/// reverse_bytes!(wide.as_mut_ptr(), ptr[0]);
+/// assert_eq!(wide, [3, 2, 1]);
+/// ```
+#[proc_macro]
+pub fn reverse_bytes(tokens: TokenStream) -> TokenStream {
+    let [ptr, len] = match parse_args(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    // out of sight of `len`, which might have its own `ptr`
+    let start = syn::Ident::new("ptr", Span::mixed_site());
+    let swaps = unsafe_block(quote! {
+        let mut i = 0;
+        while i < len / 2 {
+            ::core::ptr::swap(#start.add(i), #start.add(len - 1 - i));
+            i += 1;
+        }
+    });
+    quote!({
+        let #start = (#ptr) as *mut u8;
+        let len: usize = #len;
+        #swaps
+    })
+    .into()
+}
+
+/// Swap the values at two pointers, or `count` values starting at each when
+/// given a third argument. The ranges can't overlap.
+/// ```
+/// use plutonium::ptr_swap;
+///
+/// let mut v = [1, 2, 3, 4, 5, 6];
+/// ptr_swap!(v.as_mut_ptr(), v.as_mut_ptr().add(5));
+/// assert_eq!(v, [6, 2, 3, 4, 5, 1]);
+///
+/// ptr_swap!(v.as_mut_ptr(), v.as_mut_ptr().add(3), 3);
+/// assert_eq!(v, [4, 5, 1, 6, 2, 3]);
+/// ```
+#[proc_macro]
+pub fn ptr_swap(tokens: TokenStream) -> TokenStream {
+    let args = match Punctuated::<Expr, Token![,]>::parse_terminated.parse(tokens) {
+        Ok(args) => args.into_iter().collect::<Vec<_>>(),
+        Err(err) => return err.to_compile_error().into(),
+    };
+    match args.as_slice() {
+        [a, b] => unsafe_block(quote!(::core::ptr::swap(#a, #b))).into(),
+        [a, b, count] => unsafe_block(quote!(::core::ptr::swap_nonoverlapping(#a, #b, #count))).into(),
+        _ => syn::Error::new(
+            Span::call_site(),
+            format!("expected 2 or 3 arguments, found {}", args.len()),
+        )
+        .to_compile_error()
+        .into(),
+    }
+}
+
+/// Compare `n` bytes at two pointers, like C's `memcmp`, but with an
+/// [`Ordering`](core::cmp::Ordering) instead of a sign.
+/// ```
+/// use plutonium::memcmp;
+/// use std::cmp::Ordering;
+///
+/// let a = *b"plutonium";
+/// let b = *b"plutonic!";
+/// assert_eq!(memcmp!(a.as_ptr(), b.as_ptr(), 6), Ordering::Equal);
+/// assert_eq!(memcmp!(a.as_ptr(), b.as_ptr(), 9), Ordering::Greater);
+/// assert_eq!(memcmp!(&1u32 as *const u32, &1u32 as *const u32, 4), Ordering::Equal);
+/// ```
+#[proc_macro]
+pub fn memcmp(tokens: TokenStream) -> TokenStream {
+    let [a, b, n] = match parse_args(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let compare = unsafe_block(quote! {
+        let mut i = 0;
+        while i < n {
+            match (*a.add(i)).cmp(&*b.add(i)) {
+                ::core::cmp::Ordering::Equal => i += 1,
+                unequal => return unequal,
+            }
+        }
+        ::core::cmp::Ordering::Equal
+    });
+    quote!({
+        fn memcmp(a: *const u8, b: *const u8, n: usize) -> ::core::cmp::Ordering {
+            #compare
+        }
+        memcmp((#a) as *const u8, (#b) as *const u8, #n)
+    })
+    .into()
+}
+
+/// Give a slice a new length, keeping the pointer. Shrinking is harmless:
+/// ```
+/// use plutonium::grow_slice;
+///
+/// let v = [1, 2, 3, 4];
+/// assert_eq!(grow_slice!(&v[..], 2), [1, 2]);
+/// ```
+///
+/// Growing reads past the end of the original, which is undefined behavior:
+/// ```no_run
+/// use plutonium::grow_slice;
+///
+/// let v = [1, 2, 3, 4];
+/// let more = grow_slice!(&v[..2], 1000);
+/// println!("{:?}", more);
+/// ```
+#[proc_macro]
+pub fn grow_slice(tokens: TokenStream) -> TokenStream {
+    let [slice, len] = match parse_args(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let resized = unsafe_block(quote!(::core::slice::from_raw_parts(slice.as_ptr(), len)));
+    quote!({
+        fn grow_slice<T>(slice: &[T], len: usize) -> &[T] {
+            #resized
+        }
+        grow_slice(#slice, #len)
+    })
+    .into()
+}
+
+/// Replace the value behind a `&mut` with one computed from the old value,
+/// without needing a placeholder to leave behind.
+/// ```
+/// use plutonium::replace_with;
+///
+/// struct Token(String);
+///
+/// let mut token = Token("plain".to_string());
+/// replace_with!(&mut token, |old| Token(old.0 + "-wrapped"));
+/// assert_eq!(token.0, "plain-wrapped");
+/// ```
+///
+/// The old value is moved out for the duration of the closure, so a panic in
+/// there drops it twice, which is undefined behavior:
+/// ```no_run
+/// use plutonium::replace_with;
+///
+/// let mut name = String::from("twice");
+/// let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+///     replace_with!(&mut name, |old| -> String { panic!("dropping {}", old) });
+/// }));
+/// println!("{}", name);
+/// ```
+#[proc_macro]
+pub fn replace_with(tokens: TokenStream) -> TokenStream {
+    let [place, closure] = match parse_args(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    if !matches!(closure, Expr::Closure(_)) {
+        return syn::Error::new_spanned(closure, "expected a closure taking the old value")
+            .to_compile_error()
+            .into();
+    }
+    let replaced = unsafe_block(quote! {
+        let old = ::core::ptr::read(place);
+        ::core::ptr::write(place, f(old));
+    });
+    quote!({
+        fn replace_with<T>(place: &mut T, f: impl FnOnce(T) -> T) {
+            #replaced
+        }
+        replace_with(#place, #closure)
+    })
+    .into()
+}
+
+/// Move a value out from behind a shared reference by copying its bits.
+/// Fine if the original is never dropped:
+/// ```
+/// use plutonium::steal;
+/// use std::mem::ManuallyDrop;
+///
+/// let kept = ManuallyDrop::new(String::from("mine now"));
+/// let stolen: String = ManuallyDrop::into_inner(steal!(&kept));
+/// assert_eq!(stolen, "mine now");
+/// ```
+///
+/// Otherwise both copies own the same allocation, and dropping the second
+/// one is a double free, which is undefined behavior:
+/// ```no_run
+/// use plutonium::steal;
+///
+/// let owner = String::from("twice");
+/// let stolen: String = steal!(&owner);
+/// drop(stolen);
+/// drop(owner);
+/// ```
+#[proc_macro]
+pub fn steal(tokens: TokenStream) -> TokenStream {
+    let shared = parse_macro_input!(tokens as Expr);
+    unsafe_block(quote!(::core::ptr::read((#shared) as *const _))).into()
+}
+
+/// Cast away a pointer's `const`. Writing through the result is fine only if
+/// the pointer came from something mutable in the first place:
+/// ```
+/// use plutonium::as_mut_ptr;
+///
+/// let mut x = 1;
+/// let read_only: *const i32 = &mut x;
+/// unsafe { *as_mut_ptr!(read_only) = 2 };
+/// assert_eq!(x, 2);
+/// ```
+#[proc_macro]
+pub fn as_mut_ptr(tokens: TokenStream) -> TokenStream {
+    let ptr = parse_macro_input!(tokens as Expr);
+    quote!(((#ptr) as *const _ as *mut _)).into()
+}
+
+/// Borrow mutably through a `*const`, casting its `const` away.
+///
+/// Mutating something only ever shared, like a `&` or a `static`, is
+/// undefined behavior:
+/// ```no_run
+/// use plutonium::deref_mut;
+///
+/// let x = 1;
+/// let shared: *const i32 = &x;
+/// *deref_mut!(shared) = 2;
+/// println!("{}", x);
+/// ```
+#[proc_macro]
+pub fn deref_mut(tokens: TokenStream) -> TokenStream {
+    let ptr = parse_macro_input!(tokens as Expr);
+    unsafe_block(quote!(&mut *((#ptr) as *const _ as *mut _))).into()
+}
+
+/// A non-null, well-aligned pointer to nothing at all, for a placeholder.
+/// ```
+/// use plutonium::dangling;
+///
+/// let ptr: *mut u32 = dangling!(u32);
+/// assert!(!ptr.is_null());
+/// assert_eq!(ptr as usize % std::mem::align_of::<u32>(), 0);
+/// ```
+#[proc_macro]
+pub fn dangling(tokens: TokenStream) -> TokenStream {
+    let ty = parse_macro_input!(tokens as syn::Type);
+    quote!(::core::ptr::NonNull::<#ty>::dangling().as_ptr()).into()
+}
+
+/// A reference to nothing at all.
+///
+/// Fine for zero-sized types, but reading through any other is undefined
+/// behavior:
+/// ```no_run
+/// use plutonium::dangling_ref;
+///
+/// let nothing: &() = dangling_ref!(());
+/// let oops: &u32 = dangling_ref!(u32);
+/// println!("{:?} {}", nothing, oops);
+/// ```
+#[proc_macro]
+pub fn dangling_ref(tokens: TokenStream) -> TokenStream {
+    let ty = parse_macro_input!(tokens as syn::Type);
+    unsafe_block(quote!(&*::core::ptr::NonNull::<#ty>::dangling().as_ptr())).into()
+}
+
+/// Integer division that promises the compiler it can't divide by zero or
+/// overflow, so it can skip checking.
+/// ```
+/// use plutonium::{unchecked_div, unchecked_rem};
+///
+/// assert_eq!(unchecked_div!(17u32, 5), 3);
+/// assert_eq!(unchecked_rem!(-17i64, 5), -2);
+/// ```
+///
+/// Breaking the promise is undefined behavior, rather than a panic:
+/// ```no_run
+/// use plutonium::unchecked_div;
+///
+/// let zero = std::env::args().count() as u32 - 1;
+/// println!("{}", unchecked_div!(1u32, zero));
+/// println!("{}", unchecked_div!(i32::MIN, -1));
+/// ```
+///
+/// Only integers have checks to skip:
+/// ```compile_fail
+/// use plutonium::unchecked_div;
+///
+/// unchecked_div!(1.0f32, 0.0);
+/// ```
+#[proc_macro]
+pub fn unchecked_div(tokens: TokenStream) -> TokenStream {
+    unchecked_div_rem(tokens, "div")
+}
+
+/// Integer remainder without the divide by zero or overflow checks.
+///
+/// See [`unchecked_div!`](macro.unchecked_div.html).
+#[proc_macro]
+pub fn unchecked_rem(tokens: TokenStream) -> TokenStream {
+    unchecked_div_rem(tokens, "rem")
+}
+
+fn unchecked_div_rem(tokens: TokenStream, method: &str) -> TokenStream {
+    let [a, b] = match parse_args(tokens) {
+        Ok(args) => args,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let ints = ["u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize"]
+        .iter()
+        .map(|ty| syn::Ident::new(ty, Span::call_site()));
+    let method = syn::Ident::new(method, Span::call_site());
+    let call = unsafe_block(quote!(PlutoniumUncheckedDiv::#method(#a, #b)));
+    let div = unsafe_block(quote!(::core::hint::assert_unchecked(self.checked_div(rhs).is_some())));
+    let rem = unsafe_block(quote!(::core::hint::assert_unchecked(self.checked_rem(rhs).is_some())));
+    quote!({
+        trait PlutoniumUncheckedDiv: Sized {
+            unsafe fn div(self, rhs: Self) -> Self;
+            unsafe fn rem(self, rhs: Self) -> Self;
         }
+        #(impl PlutoniumUncheckedDiv for #ints {
+            unsafe fn div(self, rhs: Self) -> Self {
+                #div
+                self / rhs
+            }
+            unsafe fn rem(self, rhs: Self) -> Self {
+                #rem
+                self % rhs
+            }
+        })*
+        #call
+    })
+    .into()
+}
+
+/// Parse exactly `N` comma-separated expressions.
+fn parse_args<const N: usize>(tokens: TokenStream) -> syn::Result<[Expr; N]> {
+    let args: Vec<Expr> = Punctuated::<Expr, Token![,]>::parse_terminated
+        .parse(tokens)?
+        .into_iter()
+        .collect();
+    let found = args.len();
+    args.try_into().map_err(|_| {
+        syn::Error::new(
+            Span::call_site(),
+            format!("expected {} arguments, found {}", N, found),
+        )
+    })
+}
+
+/// Whether `PLUTONIUM_STRICT=1` asks for unnecessary unsafe blocks to warn.
+fn strict_mode() -> bool {
+    std::env::var("PLUTONIUM_STRICT").as_deref() == Ok("1")
+}
+
+/// Where `Vec`, `Box` and the allocator come from: `::alloc` with the `alloc`
+/// feature on, `::std` otherwise.
+fn alloc_crate() -> proc_macro2::TokenStream {
+    if cfg!(feature = "alloc") {
+        quote!(::alloc)
+    } else {
+        quote!(::std)
     }
 }
+
+/// Wrap tokens in an unsafe block that doesn't mind being unnecessary.
+fn unsafe_block(tokens: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    quote! {{
+        #[allow(unused_unsafe)]
+        unsafe { #tokens }
+    }}
+}
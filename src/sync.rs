@@ -0,0 +1,49 @@
+//! Macros for threads that don't want to wait around.
+
+use quote::quote;
+use syn::Expr;
+
+pub(crate) fn spin_wait(tokens: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let cond = match syn::parse2::<Expr>(tokens) {
+        Ok(cond) => cond,
+        Err(err) => return err.to_compile_error(),
+    };
+    quote! {
+        while !(#cond) {
+            ::core::hint::spin_loop();
+        }
+    }
+}
+
+/// Wrap a value in a freshly minted type that is `Send` (or `Sync`) whatever
+/// it holds. A proc macro crate can't export the type itself, so every
+/// expansion brings its own.
+pub(crate) fn force_impl(
+    tokens: proc_macro2::TokenStream,
+    name: &str,
+    marker: &str,
+) -> proc_macro2::TokenStream {
+    let value = match syn::parse2::<Expr>(tokens) {
+        Ok(value) => value,
+        Err(err) => return err.to_compile_error(),
+    };
+    let name = syn::Ident::new(name, proc_macro2::Span::call_site());
+    let marker = syn::Ident::new(marker, proc_macro2::Span::call_site());
+    quote!({
+        struct #name<T>(T);
+        unsafe impl<T> ::core::marker::#marker for #name<T> {}
+        impl<T> #name<T> {
+            #[allow(dead_code)]
+            fn into_inner(self) -> T {
+                self.0
+            }
+        }
+        impl<T> ::core::ops::Deref for #name<T> {
+            type Target = T;
+            fn deref(&self) -> &T {
+                &self.0
+            }
+        }
+        #name(#value)
+    })
+}
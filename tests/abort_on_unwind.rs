@@ -0,0 +1,38 @@
+use plutonium::safe;
+use std::process::Command;
+
+#[safe(abort_on_unwind)]
+fn first(v: &[u8]) -> u8 {
+    assert!(!v.is_empty(), "nothing to read");
+    *v.as_ptr()
+}
+
+// run in a child process by `panics_abort` so the abort doesn't take the
+// test harness with it
+#[test]
+#[ignore]
+fn panicking_call() {
+    let _ = std::panic::catch_unwind(|| first(&[]));
+}
+
+#[test]
+fn returns_normally() {
+    assert_eq!(first(&[7]), 7);
+}
+
+#[test]
+fn panics_abort() {
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args(["panicking_call", "--exact", "--ignored", "--nocapture"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        assert_eq!(output.status.signal(), Some(6), "expected SIGABRT");
+    }
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("nothing to read"), "{}", stderr);
+}
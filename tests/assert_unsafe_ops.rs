@@ -0,0 +1,24 @@
+mod fixture;
+
+use fixture::Fixture;
+
+#[test]
+fn says_how_many_it_found() {
+    let fixture = Fixture::new(
+        "assert_unsafe_ops",
+        &[],
+        r#"
+use plutonium::safe;
+
+#[safe(assert_unsafe_ops = 1)]
+pub fn second(ptr: *const u8) -> u8 {
+    *ptr.add(1)
+}
+"#,
+    );
+    let output = fixture.build(&[]);
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("`second` has 2 possibly unsafe operations, not 1"), "{}", stderr);
+}
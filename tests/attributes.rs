@@ -0,0 +1,36 @@
+use plutonium::safe;
+use std::process::Command;
+
+#[safe]
+#[test]
+fn safe_above_test() {
+    assert_eq!(*(&1u8 as *const u8), 1);
+}
+
+#[test]
+#[safe]
+fn test_above_safe() {
+    assert_eq!(std::ptr::read(&2u8), 2);
+}
+
+#[safe]
+#[inline]
+#[test]
+#[should_panic(expected = "boom")]
+fn should_panic_still_applies() {
+    if *(&3u8 as *const u8) == 3 {
+        panic!("boom");
+    }
+}
+
+#[test]
+fn stacked_tests_are_registered() {
+    let output = Command::new(std::env::current_exe().unwrap())
+        .arg("--list")
+        .output()
+        .unwrap();
+    let listed = String::from_utf8(output.stdout).unwrap();
+    for name in &["safe_above_test", "test_above_safe", "should_panic_still_applies"] {
+        assert!(listed.contains(&format!("{}: test", name)), "{} isn't a test", name);
+    }
+}
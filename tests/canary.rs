@@ -0,0 +1,46 @@
+mod fixture;
+
+use fixture::Fixture;
+use plutonium::safe;
+use std::{fs, ptr::null};
+
+// the messages are plutonium's, not the ones std's own debug checks give
+
+#[safe(canary)]
+#[test]
+#[should_panic(expected = "dereferencing a null pointer")]
+fn null_deref() {
+    let _: u8 = *null();
+}
+
+#[safe(canary)]
+#[test]
+#[should_panic(expected = "index 3 out of bounds")]
+fn literal_index_past_the_end() {
+    let v = [1u8, 2, 3];
+    let _ = v.get_unchecked(3);
+}
+
+#[test]
+fn gone_in_release() {
+    let fixture = Fixture::new(
+        "canary",
+        &[],
+        r#"
+use plutonium::safe;
+
+#[safe(canary)]
+pub fn doomed() -> u8 {
+    *std::ptr::null()
+}
+"#,
+    );
+    let has_canary = |output: std::process::Output, profile: &str| {
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let rlib = Fixture::target().join(profile).join("libcanary_fixture.rlib");
+        let rlib = fs::read(rlib).unwrap();
+        rlib.windows(28).any(|bytes| bytes == b"dereferencing a null pointer")
+    };
+    assert!(has_canary(fixture.build(&[]), "debug"));
+    assert!(!has_canary(fixture.build_with(&["--release"], &[]), "release"));
+}
@@ -0,0 +1,33 @@
+mod fixture;
+
+use fixture::Fixture;
+use std::fs;
+
+#[test]
+fn documents_the_original() {
+    let fixture = Fixture::new(
+        "doctest",
+        &[],
+        r#"
+use plutonium::safe;
+
+/// Reads a byte.
+#[safe(doctest)]
+pub fn deref(ptr: *const u8) -> u8 {
+    *ptr
+}
+"#,
+    );
+    let output = fixture.build(&[]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let output = fixture.cargo("doc", &["--no-deps"], &[]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let doc = Fixture::target().join("doc/doctest_fixture");
+    let safe = fs::read_to_string(doc.join("fn.deref.html")).unwrap();
+    assert!(safe.contains("pub fn deref("), "{}", safe);
+    let original = fs::read_to_string(doc.join("fn.deref_unsafe.html")).unwrap();
+    assert!(original.contains("pub unsafe fn deref_unsafe("), "{}", original);
+    assert!(original.contains("The original version of"), "{}", original);
+}
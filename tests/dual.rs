@@ -0,0 +1,31 @@
+mod fixture;
+
+use fixture::Fixture;
+
+const LIB: &str = r#"
+use plutonium::safe;
+
+#[safe(dual)]
+pub fn needs_it() -> u8 { *(&1u8 as *const u8) }
+
+#[safe(dual)]
+pub fn does_not() -> u8 { 2 }
+"#;
+
+#[test]
+fn builds_wrapped_by_default() {
+    let fixture = Fixture::new("dual", &[], LIB);
+    let output = fixture.build(&[]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn audit_reveals_unsafe_operations() {
+    let fixture = Fixture::new("dual-audit", &[], LIB);
+    let output = fixture.build_with(&["--features", "audit_safe"], &[]);
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(stderr.matches("error[E0133]").count(), 1, "{}", stderr);
+    assert!(stderr.contains("fn needs_it"), "{}", stderr);
+}
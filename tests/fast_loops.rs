@@ -0,0 +1,47 @@
+use plutonium::safe;
+
+// `i` moves on inside the body, past what the loop condition checked
+#[safe(fast_loops)]
+fn unescape(s: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < s.len() {
+        if s[i] == b'\\' {
+            i += 1;
+            out.push(s[i]);
+        } else {
+            out.push(s[i]);
+        }
+        i += 1;
+    }
+    out
+}
+
+#[safe(fast_loops)]
+#[allow(clippy::needless_range_loop)]
+fn every_other(v: &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for mut i in 0..v.len() {
+        out.push(v[i]);
+        i += 1;
+        out.push(v[i]);
+    }
+    out
+}
+
+#[test]
+fn unescapes() {
+    assert_eq!(unescape(br"a\\b\c"), br"a\bc");
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds")]
+fn trailing_escape_stays_checked() {
+    unescape(br"ab\");
+}
+
+#[test]
+#[should_panic(expected = "index out of bounds")]
+fn mut_index_stays_checked() {
+    every_other(&[1, 2]);
+}
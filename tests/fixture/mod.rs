@@ -0,0 +1,78 @@
+//! Throwaway crates for checking what our macros do at build time.
+
+use std::{
+    ffi::OsStr,
+    fs,
+    path::PathBuf,
+    process::{Command, Output},
+};
+
+pub struct Fixture {
+    pub root: PathBuf,
+}
+
+impl Fixture {
+    /// A fresh library crate depending on plutonium, with `lib` as its `src/lib.rs`.
+    pub fn new(name: &str, features: &[&str], lib: &str) -> Fixture {
+        Fixture::with_edition(name, "2018", features, lib)
+    }
+
+    /// [`Fixture::new`], on some other edition.
+    pub fn with_edition(name: &str, edition: &str, features: &[&str], lib: &str) -> Fixture {
+        let root = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            format!(
+                r#"[package]
+name = "{}-fixture"
+version = "0.0.0"
+edition = "{}"
+
+[dependencies]
+plutonium = {{ path = {:?}, features = {:?} }}
+
+[features]
+# what `#[safe(dual)]` checks for
+audit_safe = []
+"#,
+                name,
+                edition,
+                env!("CARGO_MANIFEST_DIR"),
+                features,
+            ),
+        )
+        .unwrap();
+        fs::write(root.join("src/lib.rs"), lib).unwrap();
+        Fixture { root }
+    }
+
+    /// Where every fixture builds to, shared so plutonium and its
+    /// dependencies only build once.
+    pub fn target() -> PathBuf {
+        PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("fixture-target")
+    }
+
+    /// `cargo build` the freshly written crate, so its macros expand again.
+    pub fn build(&self, env: &[(&str, &OsStr)]) -> Output {
+        self.build_with(&[], env)
+    }
+
+    /// `cargo build` with extra arguments, like `--features`.
+    pub fn build_with(&self, args: &[&str], env: &[(&str, &OsStr)]) -> Output {
+        self.cargo("build", args, env)
+    }
+
+    /// Any other cargo command, like `doc`, run in the crate.
+    pub fn cargo(&self, command: &str, args: &[&str], env: &[(&str, &OsStr)]) -> Output {
+        Command::new(env!("CARGO"))
+            .arg(command)
+            .args(args)
+            .env("CARGO_TARGET_DIR", Fixture::target())
+            .envs(env.iter().cloned())
+            .current_dir(&self.root)
+            .output()
+            .unwrap()
+    }
+}
@@ -0,0 +1,92 @@
+//! `gen` blocks need nightly and the 2024 edition:
+//! `cargo +nightly test --features nightly --test gen_blocks`
+
+mod fixture;
+
+use fixture::Fixture;
+
+#[cfg(feature = "nightly")]
+#[test]
+fn wraps_gen_blocks() {
+    let fixture = Fixture::with_edition(
+        "gen_blocks",
+        "2024",
+        &["nightly"],
+        r#"
+#![feature(gen_blocks)]
+use plutonium::safe;
+
+#[safe]
+pub fn bytes(ptr: *const u8, len: usize) -> impl Iterator<Item = u8> {
+    gen move {
+        for i in 0..len {
+            let byte = *ptr.add(i);
+            yield byte;
+            yield *ptr.add(len - 1 - i);
+        }
+    }
+}
+
+#[safe(tail)]
+pub fn nested(ptr: *const u8) -> impl Iterator<Item = u8> {
+    let first = gen { yield 0 };
+    gen move {
+        for byte in first {
+            yield byte;
+        }
+        let inner = gen move { yield *ptr };
+        for byte in inner {
+            yield byte;
+        }
+    }
+}
+"#,
+    );
+
+    let output = fixture.build(&[]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+// before 2024 `gen` is just a name, which the `nightly` feature (turned on by
+// anyone in the dependency graph) shouldn't mistake for a block
+#[test]
+fn gen_as_identifier() {
+    let fixture = Fixture::new(
+        "gen_identifier",
+        &["nightly"],
+        r#"
+use plutonium::safe;
+
+pub struct Gen {
+    pub on: bool,
+}
+
+#[safe]
+pub fn pick(ptr: *const u8, gen: bool) -> u8 {
+    if gen { *ptr } else { 0 }
+}
+
+#[safe]
+pub fn flags(ptr: *const bool, mut gen: bool, others: &[bool]) -> u8 {
+    let mut n = match gen {
+        true => 1,
+        false => 0,
+    };
+    while gen {
+        gen = !*ptr;
+    }
+    if !gen && n == 1 || *ptr == gen {
+        n += 1;
+    }
+    for gen in others {
+        if *gen { n += 1 }
+    }
+    let settings = Gen { on: gen };
+    n + settings.on as u8
+}
+"#,
+    );
+
+    let output = fixture.build(&[]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
@@ -0,0 +1,17 @@
+#[doc(hidden)]
+mod plutonium_c7da2cfc1cc75d7f {
+    #![allow(unused_unsafe)]
+    #[allow(unused_imports)]
+    use super::*;
+    pub(super) fn one() -> u8 {
+        unsafe { *(&1u8 as *const u8) }
+    }
+    pub struct Two(pub(super) u8);
+    impl Two {
+        pub(super) fn get(&self) -> u8 {
+            unsafe { std::ptr::read(&self.0) }
+        }
+    }
+}
+#[allow(unused_imports)]
+pub use plutonium_c7da2cfc1cc75d7f::*;
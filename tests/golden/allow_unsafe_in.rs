@@ -0,0 +1,13 @@
+allow_unsafe_in! {
+    fn one() -> u8 {
+        *(&1u8 as *const u8)
+    }
+
+    pub struct Two(u8);
+
+    impl Two {
+        fn get(&self) -> u8 {
+            std::ptr::read(&self.0)
+        }
+    }
+}
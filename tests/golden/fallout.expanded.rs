@@ -0,0 +1,30 @@
+fn switch(x: i32) -> String {
+    let mut s = String::new();
+    match x {
+        1 => {
+            s += "1";
+            s += "2";
+            ()
+        }
+        2 => {
+            s += "2";
+            ()
+        }
+        _ => {}
+    }
+    s
+}
+fn speaker(x: i32) -> &'static str {
+    match x {
+        12 => "12",
+        13 => {
+            log();
+            "13"
+        }
+        14 => {
+            "14";
+            "lol"
+        }
+        _ => "lol",
+    }
+}
@@ -0,0 +1,20 @@
+#[fallout]
+fn switch(x: i32) -> String {
+    let mut s = String::new();
+    match x {
+        1 => s += "1",
+        2 => s += "2",
+        _ => (),
+    }
+    s
+}
+
+#[fallout]
+fn speaker(x: i32) -> &'static str {
+    match x {
+        12 => { "12"; break; },
+        13 => { log(); break "13"; },
+        14 => "14",
+        _ => "lol",
+    }
+}
@@ -0,0 +1,294 @@
+fn optimized() {}
+fn optimized() {
+    {
+        #[allow(unused_unsafe)]
+        unsafe {
+            (*v.get_unchecked_mut(0)) = (*v.get_unchecked(1));
+            (*v.get_unchecked(0))
+        }
+    }
+}
+fn optimized() {
+    {
+        trait PlutoniumWrapping: Copy {
+            fn add(self, rhs: Self) -> Self;
+            fn sub(self, rhs: Self) -> Self;
+            fn mul(self, rhs: Self) -> Self;
+            fn neg(self) -> Self;
+        }
+        impl PlutoniumWrapping for u8 {
+            fn add(self, rhs: Self) -> Self {
+                self.wrapping_add(rhs)
+            }
+            fn sub(self, rhs: Self) -> Self {
+                self.wrapping_sub(rhs)
+            }
+            fn mul(self, rhs: Self) -> Self {
+                self.wrapping_mul(rhs)
+            }
+            fn neg(self) -> Self {
+                self.wrapping_neg()
+            }
+        }
+        impl PlutoniumWrapping for u16 {
+            fn add(self, rhs: Self) -> Self {
+                self.wrapping_add(rhs)
+            }
+            fn sub(self, rhs: Self) -> Self {
+                self.wrapping_sub(rhs)
+            }
+            fn mul(self, rhs: Self) -> Self {
+                self.wrapping_mul(rhs)
+            }
+            fn neg(self) -> Self {
+                self.wrapping_neg()
+            }
+        }
+        impl PlutoniumWrapping for u32 {
+            fn add(self, rhs: Self) -> Self {
+                self.wrapping_add(rhs)
+            }
+            fn sub(self, rhs: Self) -> Self {
+                self.wrapping_sub(rhs)
+            }
+            fn mul(self, rhs: Self) -> Self {
+                self.wrapping_mul(rhs)
+            }
+            fn neg(self) -> Self {
+                self.wrapping_neg()
+            }
+        }
+        impl PlutoniumWrapping for u64 {
+            fn add(self, rhs: Self) -> Self {
+                self.wrapping_add(rhs)
+            }
+            fn sub(self, rhs: Self) -> Self {
+                self.wrapping_sub(rhs)
+            }
+            fn mul(self, rhs: Self) -> Self {
+                self.wrapping_mul(rhs)
+            }
+            fn neg(self) -> Self {
+                self.wrapping_neg()
+            }
+        }
+        impl PlutoniumWrapping for u128 {
+            fn add(self, rhs: Self) -> Self {
+                self.wrapping_add(rhs)
+            }
+            fn sub(self, rhs: Self) -> Self {
+                self.wrapping_sub(rhs)
+            }
+            fn mul(self, rhs: Self) -> Self {
+                self.wrapping_mul(rhs)
+            }
+            fn neg(self) -> Self {
+                self.wrapping_neg()
+            }
+        }
+        impl PlutoniumWrapping for usize {
+            fn add(self, rhs: Self) -> Self {
+                self.wrapping_add(rhs)
+            }
+            fn sub(self, rhs: Self) -> Self {
+                self.wrapping_sub(rhs)
+            }
+            fn mul(self, rhs: Self) -> Self {
+                self.wrapping_mul(rhs)
+            }
+            fn neg(self) -> Self {
+                self.wrapping_neg()
+            }
+        }
+        impl PlutoniumWrapping for i8 {
+            fn add(self, rhs: Self) -> Self {
+                self.wrapping_add(rhs)
+            }
+            fn sub(self, rhs: Self) -> Self {
+                self.wrapping_sub(rhs)
+            }
+            fn mul(self, rhs: Self) -> Self {
+                self.wrapping_mul(rhs)
+            }
+            fn neg(self) -> Self {
+                self.wrapping_neg()
+            }
+        }
+        impl PlutoniumWrapping for i16 {
+            fn add(self, rhs: Self) -> Self {
+                self.wrapping_add(rhs)
+            }
+            fn sub(self, rhs: Self) -> Self {
+                self.wrapping_sub(rhs)
+            }
+            fn mul(self, rhs: Self) -> Self {
+                self.wrapping_mul(rhs)
+            }
+            fn neg(self) -> Self {
+                self.wrapping_neg()
+            }
+        }
+        impl PlutoniumWrapping for i32 {
+            fn add(self, rhs: Self) -> Self {
+                self.wrapping_add(rhs)
+            }
+            fn sub(self, rhs: Self) -> Self {
+                self.wrapping_sub(rhs)
+            }
+            fn mul(self, rhs: Self) -> Self {
+                self.wrapping_mul(rhs)
+            }
+            fn neg(self) -> Self {
+                self.wrapping_neg()
+            }
+        }
+        impl PlutoniumWrapping for i64 {
+            fn add(self, rhs: Self) -> Self {
+                self.wrapping_add(rhs)
+            }
+            fn sub(self, rhs: Self) -> Self {
+                self.wrapping_sub(rhs)
+            }
+            fn mul(self, rhs: Self) -> Self {
+                self.wrapping_mul(rhs)
+            }
+            fn neg(self) -> Self {
+                self.wrapping_neg()
+            }
+        }
+        impl PlutoniumWrapping for i128 {
+            fn add(self, rhs: Self) -> Self {
+                self.wrapping_add(rhs)
+            }
+            fn sub(self, rhs: Self) -> Self {
+                self.wrapping_sub(rhs)
+            }
+            fn mul(self, rhs: Self) -> Self {
+                self.wrapping_mul(rhs)
+            }
+            fn neg(self) -> Self {
+                self.wrapping_neg()
+            }
+        }
+        impl PlutoniumWrapping for isize {
+            fn add(self, rhs: Self) -> Self {
+                self.wrapping_add(rhs)
+            }
+            fn sub(self, rhs: Self) -> Self {
+                self.wrapping_sub(rhs)
+            }
+            fn mul(self, rhs: Self) -> Self {
+                self.wrapping_mul(rhs)
+            }
+            fn neg(self) -> Self {
+                self.wrapping_neg()
+            }
+        }
+        impl PlutoniumWrapping for f32 {
+            fn add(self, rhs: Self) -> Self {
+                self + rhs
+            }
+            fn sub(self, rhs: Self) -> Self {
+                self - rhs
+            }
+            fn mul(self, rhs: Self) -> Self {
+                self * rhs
+            }
+            fn neg(self) -> Self {
+                -self
+            }
+        }
+        impl PlutoniumWrapping for f64 {
+            fn add(self, rhs: Self) -> Self {
+                self + rhs
+            }
+            fn sub(self, rhs: Self) -> Self {
+                self - rhs
+            }
+            fn mul(self, rhs: Self) -> Self {
+                self * rhs
+            }
+            fn neg(self) -> Self {
+                -self
+            }
+        }
+        struct PlutoniumOperands<'a, L, R>(&'a L, &'a R);
+        struct PlutoniumNumbers;
+        struct PlutoniumOperators;
+        trait PlutoniumNumberKind {
+            fn plutonium_kind(&self) -> PlutoniumNumbers {
+                PlutoniumNumbers
+            }
+        }
+        impl<T: PlutoniumWrapping> PlutoniumNumberKind for PlutoniumOperands<'_, T, T> {}
+        trait PlutoniumOperatorKind {
+            fn plutonium_kind(&self) -> PlutoniumOperators {
+                PlutoniumOperators
+            }
+        }
+        impl<L, R> PlutoniumOperatorKind for &PlutoniumOperands<'_, L, R> {}
+        impl PlutoniumNumbers {
+            fn add<T: PlutoniumWrapping>(self, lhs: T, rhs: T) -> T {
+                PlutoniumWrapping::add(lhs, rhs)
+            }
+            fn add_assign<T: PlutoniumWrapping>(self, place: &mut T, rhs: T) {
+                *place = PlutoniumWrapping::add(*place, rhs);
+            }
+            fn sub<T: PlutoniumWrapping>(self, lhs: T, rhs: T) -> T {
+                PlutoniumWrapping::sub(lhs, rhs)
+            }
+            fn sub_assign<T: PlutoniumWrapping>(self, place: &mut T, rhs: T) {
+                *place = PlutoniumWrapping::sub(*place, rhs);
+            }
+            fn mul<T: PlutoniumWrapping>(self, lhs: T, rhs: T) -> T {
+                PlutoniumWrapping::mul(lhs, rhs)
+            }
+            fn mul_assign<T: PlutoniumWrapping>(self, place: &mut T, rhs: T) {
+                *place = PlutoniumWrapping::mul(*place, rhs);
+            }
+            fn neg<T: PlutoniumWrapping>(self, operand: T) -> T {
+                PlutoniumWrapping::neg(operand)
+            }
+        }
+        impl PlutoniumOperators {
+            fn add<L: ::core::ops::Add<R>, R>(self, lhs: L, rhs: R) -> L::Output {
+                ::core::ops::Add::add(lhs, rhs)
+            }
+            fn add_assign<L: ::core::ops::AddAssign<R>, R>(self, place: &mut L, rhs: R) {
+                ::core::ops::AddAssign::add_assign(place, rhs)
+            }
+            fn sub<L: ::core::ops::Sub<R>, R>(self, lhs: L, rhs: R) -> L::Output {
+                ::core::ops::Sub::sub(lhs, rhs)
+            }
+            fn sub_assign<L: ::core::ops::SubAssign<R>, R>(self, place: &mut L, rhs: R) {
+                ::core::ops::SubAssign::sub_assign(place, rhs)
+            }
+            fn mul<L: ::core::ops::Mul<R>, R>(self, lhs: L, rhs: R) -> L::Output {
+                ::core::ops::Mul::mul(lhs, rhs)
+            }
+            fn mul_assign<L: ::core::ops::MulAssign<R>, R>(self, place: &mut L, rhs: R) {
+                ::core::ops::MulAssign::mul_assign(place, rhs)
+            }
+            fn neg<T: ::core::ops::Neg>(self, operand: T) -> T::Output {
+                ::core::ops::Neg::neg(operand)
+            }
+        }
+        {
+            let rhs = 1;
+            let place = &mut x;
+            (&PlutoniumOperands(&*place, &rhs)).plutonium_kind().add_assign(place, rhs);
+        };
+        match (
+            match x {
+                operand => {
+                    (&PlutoniumOperands(&operand, &operand))
+                        .plutonium_kind()
+                        .neg(operand)
+                }
+            },
+            2,
+        ) {
+            (lhs, rhs) => (&PlutoniumOperands(&lhs, &rhs)).plutonium_kind().mul(lhs, rhs),
+        }
+    }
+}
@@ -0,0 +1,11 @@
+optimize!(vec.sort());
+
+optimize!(elide = "bounds", {
+    v[0] = v[1];
+    v[0]
+});
+
+optimize!(elide = "overflow", {
+    x += 1;
+    -x * 2
+});
@@ -0,0 +1,19 @@
+fn bits(x: f32) -> u32 {
+    #[allow(unused_unsafe)] unsafe { std::mem::transmute(x) }
+}
+/// Docs stay put.
+#[inline]
+pub fn deref(ptr: *const u8) -> u8 {
+    #[allow(unused_unsafe)] unsafe { *ptr }
+}
+fn nested() -> u8 {
+    #[allow(unused_unsafe)]
+    unsafe {
+        const ONE: u8 = { #[allow(unused_unsafe)] unsafe { *(&1u8 as *const u8) } };
+        ONE + const { #[allow(unused_unsafe)] unsafe { *(&2u8 as *const u8) } }
+    }
+}
+#[target_feature(enable = "sse2")]
+unsafe fn featured(v: &[u8]) -> u8 {
+    #[allow(unused_unsafe)] unsafe { *v.get_unchecked(0) }
+}
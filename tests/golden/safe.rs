@@ -0,0 +1,23 @@
+#[safe]
+fn bits(x: f32) -> u32 {
+    std::mem::transmute(x)
+}
+
+/// Docs stay put.
+#[inline]
+#[safe]
+pub unsafe fn deref(ptr: *const u8) -> u8 {
+    *ptr
+}
+
+#[safe]
+fn nested() -> u8 {
+    const ONE: u8 = *(&1u8 as *const u8);
+    ONE + const { *(&2u8 as *const u8) }
+}
+
+#[safe]
+#[target_feature(enable = "sse2")]
+unsafe fn featured(v: &[u8]) -> u8 {
+    *v.get_unchecked(0)
+}
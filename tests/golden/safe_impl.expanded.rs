@@ -0,0 +1,273 @@
+struct Wrapper<T>(*const T);
+impl<T: Copy> Wrapper<T> {
+    const SIZE: u32 = {
+        #[allow(unused_unsafe)]
+        unsafe { std::mem::transmute(std::mem::size_of::<T>() as i32) }
+    };
+    fn get(&self) -> T {
+        #[allow(unused_unsafe)] unsafe { *self.0 }
+    }
+}
+unsafe impl<T: Copy> Source<T> for Wrapper<T>
+where
+    T: Default,
+{
+    fn is_set(&self) -> bool {
+        #[allow(unused_unsafe)] unsafe { !self.0.is_null() }
+    }
+    unsafe fn read(&self, (offset, _): (usize, usize)) -> T {
+        #[allow(unused_unsafe)] unsafe { *self.0.add(offset) }
+    }
+}
+impl<T: Copy> Wrapper<T>
+where
+    T: Default,
+{
+    /// Calls `read` from the trait, no `unsafe` required.
+    #[allow(dead_code)]
+    pub fn source_read_safe(&self, arg1: (usize, usize)) -> T {
+        { #[allow(unused_unsafe)] unsafe { <Self as Source<T>>::read(self, arg1) } }
+    }
+}
+const _: () = {
+    trait PlutoniumUnchecked: Copy {
+        fn add(self, rhs: Self) -> Self;
+        fn sub(self, rhs: Self) -> Self;
+        fn mul(self, rhs: Self) -> Self;
+    }
+    impl PlutoniumUnchecked for u8 {
+        fn add(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_add(rhs) }
+        }
+        fn sub(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_sub(rhs) }
+        }
+        fn mul(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_mul(rhs) }
+        }
+    }
+    impl PlutoniumUnchecked for u16 {
+        fn add(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_add(rhs) }
+        }
+        fn sub(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_sub(rhs) }
+        }
+        fn mul(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_mul(rhs) }
+        }
+    }
+    impl PlutoniumUnchecked for u32 {
+        fn add(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_add(rhs) }
+        }
+        fn sub(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_sub(rhs) }
+        }
+        fn mul(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_mul(rhs) }
+        }
+    }
+    impl PlutoniumUnchecked for u64 {
+        fn add(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_add(rhs) }
+        }
+        fn sub(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_sub(rhs) }
+        }
+        fn mul(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_mul(rhs) }
+        }
+    }
+    impl PlutoniumUnchecked for u128 {
+        fn add(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_add(rhs) }
+        }
+        fn sub(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_sub(rhs) }
+        }
+        fn mul(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_mul(rhs) }
+        }
+    }
+    impl PlutoniumUnchecked for usize {
+        fn add(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_add(rhs) }
+        }
+        fn sub(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_sub(rhs) }
+        }
+        fn mul(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_mul(rhs) }
+        }
+    }
+    impl PlutoniumUnchecked for i8 {
+        fn add(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_add(rhs) }
+        }
+        fn sub(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_sub(rhs) }
+        }
+        fn mul(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_mul(rhs) }
+        }
+    }
+    impl PlutoniumUnchecked for i16 {
+        fn add(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_add(rhs) }
+        }
+        fn sub(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_sub(rhs) }
+        }
+        fn mul(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_mul(rhs) }
+        }
+    }
+    impl PlutoniumUnchecked for i32 {
+        fn add(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_add(rhs) }
+        }
+        fn sub(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_sub(rhs) }
+        }
+        fn mul(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_mul(rhs) }
+        }
+    }
+    impl PlutoniumUnchecked for i64 {
+        fn add(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_add(rhs) }
+        }
+        fn sub(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_sub(rhs) }
+        }
+        fn mul(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_mul(rhs) }
+        }
+    }
+    impl PlutoniumUnchecked for i128 {
+        fn add(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_add(rhs) }
+        }
+        fn sub(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_sub(rhs) }
+        }
+        fn mul(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_mul(rhs) }
+        }
+    }
+    impl PlutoniumUnchecked for isize {
+        fn add(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_add(rhs) }
+        }
+        fn sub(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_sub(rhs) }
+        }
+        fn mul(self, rhs: Self) -> Self {
+            unsafe { self.unchecked_mul(rhs) }
+        }
+    }
+    impl PlutoniumUnchecked for f32 {
+        fn add(self, rhs: Self) -> Self {
+            self + rhs
+        }
+        fn sub(self, rhs: Self) -> Self {
+            self - rhs
+        }
+        fn mul(self, rhs: Self) -> Self {
+            self * rhs
+        }
+    }
+    impl PlutoniumUnchecked for f64 {
+        fn add(self, rhs: Self) -> Self {
+            self + rhs
+        }
+        fn sub(self, rhs: Self) -> Self {
+            self - rhs
+        }
+        fn mul(self, rhs: Self) -> Self {
+            self * rhs
+        }
+    }
+    struct PlutoniumOperands<'a, L, R>(&'a L, &'a R);
+    struct PlutoniumNumbers;
+    struct PlutoniumOperators;
+    trait PlutoniumNumberKind {
+        fn plutonium_kind(&self) -> PlutoniumNumbers {
+            PlutoniumNumbers
+        }
+    }
+    impl<T: PlutoniumUnchecked> PlutoniumNumberKind for PlutoniumOperands<'_, T, T> {}
+    trait PlutoniumOperatorKind {
+        fn plutonium_kind(&self) -> PlutoniumOperators {
+            PlutoniumOperators
+        }
+    }
+    impl<L, R> PlutoniumOperatorKind for &PlutoniumOperands<'_, L, R> {}
+    impl PlutoniumNumbers {
+        fn add<T: PlutoniumUnchecked>(self, lhs: T, rhs: T) -> T {
+            PlutoniumUnchecked::add(lhs, rhs)
+        }
+        fn add_assign<T: PlutoniumUnchecked>(self, place: &mut T, rhs: T) {
+            *place = PlutoniumUnchecked::add(*place, rhs);
+        }
+        fn sub<T: PlutoniumUnchecked>(self, lhs: T, rhs: T) -> T {
+            PlutoniumUnchecked::sub(lhs, rhs)
+        }
+        fn sub_assign<T: PlutoniumUnchecked>(self, place: &mut T, rhs: T) {
+            *place = PlutoniumUnchecked::sub(*place, rhs);
+        }
+        fn mul<T: PlutoniumUnchecked>(self, lhs: T, rhs: T) -> T {
+            PlutoniumUnchecked::mul(lhs, rhs)
+        }
+        fn mul_assign<T: PlutoniumUnchecked>(self, place: &mut T, rhs: T) {
+            *place = PlutoniumUnchecked::mul(*place, rhs);
+        }
+    }
+    impl PlutoniumOperators {
+        fn add<L: ::core::ops::Add<R>, R>(self, lhs: L, rhs: R) -> L::Output {
+            ::core::ops::Add::add(lhs, rhs)
+        }
+        fn add_assign<L: ::core::ops::AddAssign<R>, R>(self, place: &mut L, rhs: R) {
+            ::core::ops::AddAssign::add_assign(place, rhs)
+        }
+        fn sub<L: ::core::ops::Sub<R>, R>(self, lhs: L, rhs: R) -> L::Output {
+            ::core::ops::Sub::sub(lhs, rhs)
+        }
+        fn sub_assign<L: ::core::ops::SubAssign<R>, R>(self, place: &mut L, rhs: R) {
+            ::core::ops::SubAssign::sub_assign(place, rhs)
+        }
+        fn mul<L: ::core::ops::Mul<R>, R>(self, lhs: L, rhs: R) -> L::Output {
+            ::core::ops::Mul::mul(lhs, rhs)
+        }
+        fn mul_assign<L: ::core::ops::MulAssign<R>, R>(self, place: &mut L, rhs: R) {
+            ::core::ops::MulAssign::mul_assign(place, rhs)
+        }
+    }
+    impl Counter {
+        fn next(&mut self) -> u32 {
+            #[allow(unused_unsafe)]
+            unsafe {
+                {
+                    let rhs = 1;
+                    let place = &mut self.0;
+                    (&PlutoniumOperands(&*place, &rhs))
+                        .plutonium_kind()
+                        .add_assign(place, rhs);
+                };
+                self.0
+            }
+        }
+        fn skip(&mut self, n: u32) -> u32 {
+            #[allow(unused_unsafe)]
+            unsafe {
+                self.0 = match (self.0, n) {
+                    (lhs, rhs) => {
+                        (&PlutoniumOperands(&lhs, &rhs)).plutonium_kind().mul(lhs, rhs)
+                    }
+                };
+                self.0
+            }
+        }
+    }
+};
@@ -0,0 +1,37 @@
+struct Wrapper<T>(*const T);
+
+#[safe]
+impl<T: Copy> Wrapper<T> {
+    const SIZE: u32 = std::mem::transmute(std::mem::size_of::<T>() as i32);
+
+    unsafe fn get(&self) -> T {
+        *self.0
+    }
+}
+
+#[safe]
+unsafe impl<T: Copy> Source<T> for Wrapper<T>
+where
+    T: Default,
+{
+    fn is_set(&self) -> bool {
+        !self.0.is_null()
+    }
+
+    unsafe fn read(&self, (offset, _): (usize, usize)) -> T {
+        *self.0.add(offset)
+    }
+}
+
+#[safe(unchecked_arith)]
+impl Counter {
+    fn next(&mut self) -> u32 {
+        self.0 += 1;
+        self.0
+    }
+
+    fn skip(&mut self, n: u32) -> u32 {
+        self.0 = self.0 * n;
+        self.0
+    }
+}
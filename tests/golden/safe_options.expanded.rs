@@ -0,0 +1,371 @@
+fn sum(v: &[u32]) -> u32 {
+    #[allow(unused_unsafe)]
+    unsafe {
+        let mut total = 0;
+        for i in 0..v.len() {
+            total += (*v.get_unchecked(i));
+        }
+        total
+    }
+}
+fn first(v: &[u32]) -> u32 {
+    #[allow(unused_unsafe)]
+    unsafe { *({ #[allow(unused_unsafe)] unsafe { v.get_unchecked(0) } }) }
+}
+fn looked_up(v: &[u32], names: &HashMap<&str, u32>, key: &str, i: u8) -> u32 {
+    #[allow(unused_unsafe)]
+    unsafe {
+        ({ #[allow(unused_unsafe)] unsafe { v.get_unchecked(i as usize) } })
+            + ({ #[allow(unused_unsafe)] unsafe { v.get_unchecked(v.len() - 1) } })
+            + names.get(key).unwrap()
+    }
+}
+fn bits(x: f32) -> u32 {
+    let doubled = x * 2.0;
+    { #[allow(unused_unsafe)] unsafe { std::mem::transmute(doubled) } }
+}
+fn traced() {
+    #[allow(unused_unsafe)]
+    unsafe {
+        struct PlutoniumTrace(&'static str);
+        impl Drop for PlutoniumTrace {
+            fn drop(&mut self) {
+                eprintln!("[plutonium] exit {}", self.0);
+            }
+        }
+        eprintln!("[plutonium] enter {}", "traced");
+        let _trace = PlutoniumTrace("traced");
+    }
+}
+#[no_mangle]
+extern "C" fn exported(a: *const i32) -> i32 {
+    #[allow(unused_unsafe)] unsafe { *a }
+}
+/// Turns floats into ints.
+///
+/// SAFETY: ported from C
+fn reasoned(x: f32) -> u32 {
+    #[allow(unused_unsafe)] unsafe { std::mem::transmute(x) }
+}
+/// Reads a byte.
+pub fn read(ptr: *const u8) -> u8 {
+    #[allow(unused_unsafe)] unsafe { *ptr }
+}
+#[cfg(doc)]
+/// The original version of [`read`], before `#[safe]`.
+pub unsafe fn read_unsafe(ptr: *const u8) -> u8 {
+    *ptr
+}
+fn mix(a: u8, b: f32) -> f32 {
+    #[allow(unused_unsafe)]
+    unsafe {
+        trait PlutoniumUnchecked: Copy {
+            fn add(self, rhs: Self) -> Self;
+            fn sub(self, rhs: Self) -> Self;
+            fn mul(self, rhs: Self) -> Self;
+        }
+        impl PlutoniumUnchecked for u8 {
+            fn add(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_add(rhs) }
+            }
+            fn sub(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_sub(rhs) }
+            }
+            fn mul(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_mul(rhs) }
+            }
+        }
+        impl PlutoniumUnchecked for u16 {
+            fn add(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_add(rhs) }
+            }
+            fn sub(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_sub(rhs) }
+            }
+            fn mul(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_mul(rhs) }
+            }
+        }
+        impl PlutoniumUnchecked for u32 {
+            fn add(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_add(rhs) }
+            }
+            fn sub(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_sub(rhs) }
+            }
+            fn mul(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_mul(rhs) }
+            }
+        }
+        impl PlutoniumUnchecked for u64 {
+            fn add(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_add(rhs) }
+            }
+            fn sub(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_sub(rhs) }
+            }
+            fn mul(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_mul(rhs) }
+            }
+        }
+        impl PlutoniumUnchecked for u128 {
+            fn add(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_add(rhs) }
+            }
+            fn sub(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_sub(rhs) }
+            }
+            fn mul(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_mul(rhs) }
+            }
+        }
+        impl PlutoniumUnchecked for usize {
+            fn add(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_add(rhs) }
+            }
+            fn sub(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_sub(rhs) }
+            }
+            fn mul(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_mul(rhs) }
+            }
+        }
+        impl PlutoniumUnchecked for i8 {
+            fn add(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_add(rhs) }
+            }
+            fn sub(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_sub(rhs) }
+            }
+            fn mul(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_mul(rhs) }
+            }
+        }
+        impl PlutoniumUnchecked for i16 {
+            fn add(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_add(rhs) }
+            }
+            fn sub(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_sub(rhs) }
+            }
+            fn mul(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_mul(rhs) }
+            }
+        }
+        impl PlutoniumUnchecked for i32 {
+            fn add(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_add(rhs) }
+            }
+            fn sub(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_sub(rhs) }
+            }
+            fn mul(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_mul(rhs) }
+            }
+        }
+        impl PlutoniumUnchecked for i64 {
+            fn add(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_add(rhs) }
+            }
+            fn sub(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_sub(rhs) }
+            }
+            fn mul(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_mul(rhs) }
+            }
+        }
+        impl PlutoniumUnchecked for i128 {
+            fn add(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_add(rhs) }
+            }
+            fn sub(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_sub(rhs) }
+            }
+            fn mul(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_mul(rhs) }
+            }
+        }
+        impl PlutoniumUnchecked for isize {
+            fn add(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_add(rhs) }
+            }
+            fn sub(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_sub(rhs) }
+            }
+            fn mul(self, rhs: Self) -> Self {
+                unsafe { self.unchecked_mul(rhs) }
+            }
+        }
+        impl PlutoniumUnchecked for f32 {
+            fn add(self, rhs: Self) -> Self {
+                self + rhs
+            }
+            fn sub(self, rhs: Self) -> Self {
+                self - rhs
+            }
+            fn mul(self, rhs: Self) -> Self {
+                self * rhs
+            }
+        }
+        impl PlutoniumUnchecked for f64 {
+            fn add(self, rhs: Self) -> Self {
+                self + rhs
+            }
+            fn sub(self, rhs: Self) -> Self {
+                self - rhs
+            }
+            fn mul(self, rhs: Self) -> Self {
+                self * rhs
+            }
+        }
+        struct PlutoniumOperands<'a, L, R>(&'a L, &'a R);
+        struct PlutoniumNumbers;
+        struct PlutoniumOperators;
+        trait PlutoniumNumberKind {
+            fn plutonium_kind(&self) -> PlutoniumNumbers {
+                PlutoniumNumbers
+            }
+        }
+        impl<T: PlutoniumUnchecked> PlutoniumNumberKind for PlutoniumOperands<'_, T, T> {}
+        trait PlutoniumOperatorKind {
+            fn plutonium_kind(&self) -> PlutoniumOperators {
+                PlutoniumOperators
+            }
+        }
+        impl<L, R> PlutoniumOperatorKind for &PlutoniumOperands<'_, L, R> {}
+        impl PlutoniumNumbers {
+            fn add<T: PlutoniumUnchecked>(self, lhs: T, rhs: T) -> T {
+                PlutoniumUnchecked::add(lhs, rhs)
+            }
+            fn add_assign<T: PlutoniumUnchecked>(self, place: &mut T, rhs: T) {
+                *place = PlutoniumUnchecked::add(*place, rhs);
+            }
+            fn sub<T: PlutoniumUnchecked>(self, lhs: T, rhs: T) -> T {
+                PlutoniumUnchecked::sub(lhs, rhs)
+            }
+            fn sub_assign<T: PlutoniumUnchecked>(self, place: &mut T, rhs: T) {
+                *place = PlutoniumUnchecked::sub(*place, rhs);
+            }
+            fn mul<T: PlutoniumUnchecked>(self, lhs: T, rhs: T) -> T {
+                PlutoniumUnchecked::mul(lhs, rhs)
+            }
+            fn mul_assign<T: PlutoniumUnchecked>(self, place: &mut T, rhs: T) {
+                *place = PlutoniumUnchecked::mul(*place, rhs);
+            }
+        }
+        impl PlutoniumOperators {
+            fn add<L: ::core::ops::Add<R>, R>(self, lhs: L, rhs: R) -> L::Output {
+                ::core::ops::Add::add(lhs, rhs)
+            }
+            fn add_assign<L: ::core::ops::AddAssign<R>, R>(self, place: &mut L, rhs: R) {
+                ::core::ops::AddAssign::add_assign(place, rhs)
+            }
+            fn sub<L: ::core::ops::Sub<R>, R>(self, lhs: L, rhs: R) -> L::Output {
+                ::core::ops::Sub::sub(lhs, rhs)
+            }
+            fn sub_assign<L: ::core::ops::SubAssign<R>, R>(self, place: &mut L, rhs: R) {
+                ::core::ops::SubAssign::sub_assign(place, rhs)
+            }
+            fn mul<L: ::core::ops::Mul<R>, R>(self, lhs: L, rhs: R) -> L::Output {
+                ::core::ops::Mul::mul(lhs, rhs)
+            }
+            fn mul_assign<L: ::core::ops::MulAssign<R>, R>(self, place: &mut L, rhs: R) {
+                ::core::ops::MulAssign::mul_assign(place, rhs)
+            }
+        }
+        let mut n = match (
+            match (a, 2) {
+                (lhs, rhs) => {
+                    (&PlutoniumOperands(&lhs, &rhs)).plutonium_kind().mul(lhs, rhs)
+                }
+            },
+            1,
+        ) {
+            (lhs, rhs) => (&PlutoniumOperands(&lhs, &rhs)).plutonium_kind().add(lhs, rhs),
+        };
+        {
+            let rhs = a;
+            let place = &mut n;
+            (&PlutoniumOperands(&*place, &rhs)).plutonium_kind().sub_assign(place, rhs);
+        };
+        match (n as f32, b) {
+            (lhs, rhs) => (&PlutoniumOperands(&lhs, &rhs)).plutonium_kind().mul(lhs, rhs),
+        }
+    }
+}
+fn canaries(v: &[u8]) -> u8 {
+    #[allow(unused_unsafe)]
+    unsafe {
+        *{
+            debug_assert!(3 < v.len(), "index {} out of bounds", 3);
+            v.get_unchecked(3)
+        }
+            + *{
+                debug_assert!(false, "dereferencing a null pointer");
+                std::ptr::null::<u8>()
+            }
+    }
+}
+fn transmutes(x: f32) -> u32 {
+    #[allow(unused_unsafe)]
+    unsafe {
+        unsafe fn plutonium_transmute<A, B>(value: A) -> B {
+            debug_assert_eq!(
+                ::core::mem::size_of:: < A > (), ::core::mem::size_of:: < B > (),
+                "transmute between differently sized types",
+            );
+            {
+                #[allow(unused_unsafe)]
+                unsafe {
+                    let value = ::core::mem::ManuallyDrop::new(value);
+                    ::core::ptr::read_unaligned(&*value as *const A as *const B)
+                }
+            }
+        }
+        let y: i32 = plutonium_transmute(x);
+        plutonium_transmute::<i32, u32>(y)
+    }
+}
+/// Reads through a pointer.
+///
+/** Before `#[safe]`:
+ ```ignore
+pub unsafe fn shown(ptr: *const u8) -> u8 {
+    let x = *ptr;
+    x
+}
+ ```*/
+pub fn shown(ptr: *const u8) -> u8 {
+    #[allow(unused_unsafe)]
+    unsafe {
+        let x = *ptr;
+        x
+    }
+}
+fn unwraps(v: &[u8]) -> u8 {
+    #[allow(unused_unsafe)]
+    unsafe {
+        ({ #[allow(unused_unsafe)] unsafe { v.first().unwrap_unchecked() } })
+            + ({ #[allow(unused_unsafe)] unsafe { v.last().unwrap_unchecked() } })
+            + v.get(1).copied().unwrap_or(0)
+    }
+}
+fn aborts(v: &[u8]) -> u8 {
+    #[allow(unused_unsafe)]
+    unsafe {
+        struct PlutoniumAbortOnDrop;
+        impl Drop for PlutoniumAbortOnDrop {
+            fn drop(&mut self) {
+                if ::std::thread::panicking() {
+                    ::std::process::abort();
+                }
+            }
+        }
+        let _abort = PlutoniumAbortOnDrop;
+        *v.as_ptr()
+    }
+}
+fn allows() -> &'static u8 {
+    #[allow(unused_unsafe, invalid_value, clippy::uninit_assumed_init)]
+    unsafe { std::mem::zeroed() }
+}
@@ -0,0 +1,84 @@
+#[safe(fast_loops)]
+fn sum(v: &[u32]) -> u32 {
+    let mut total = 0;
+    for i in 0..v.len() {
+        total += v[i];
+    }
+    total
+}
+
+#[safe(unchecked_get)]
+fn first(v: &[u32]) -> u32 {
+    *v.get(0).unwrap()
+}
+
+#[safe(unchecked_get)]
+fn looked_up(v: &[u32], names: &HashMap<&str, u32>, key: &str, i: u8) -> u32 {
+    v.get(i as usize).unwrap() + v.get(v.len() - 1).unwrap() + names.get(key).unwrap()
+}
+
+#[safe(tail)]
+fn bits(x: f32) -> u32 {
+    let doubled = x * 2.0;
+    std::mem::transmute(doubled)
+}
+
+#[safe(trace)]
+fn traced() {}
+
+#[safe(export = "C")]
+fn exported(a: *const i32) -> i32 {
+    *a
+}
+
+/// Turns floats into ints.
+#[safe(reason = "ported from C")]
+fn reasoned(x: f32) -> u32 {
+    std::mem::transmute(x)
+}
+
+/// Reads a byte.
+#[safe(doctest)]
+pub unsafe fn read(ptr: *const u8) -> u8 {
+    *ptr
+}
+
+#[safe(unchecked_arith)]
+fn mix(a: u8, b: f32) -> f32 {
+    let mut n = a * 2 + 1;
+    n -= a;
+    n as f32 * b
+}
+
+#[safe(canary)]
+fn canaries(v: &[u8]) -> u8 {
+    *v.get_unchecked(3) + *std::ptr::null::<u8>()
+}
+
+#[safe(checked_transmute)]
+fn transmutes(x: f32) -> u32 {
+    let y: i32 = std::mem::transmute(x);
+    transmute::<i32, u32>(y)
+}
+
+/// Reads through a pointer.
+#[safe(show_original)]
+pub unsafe fn shown(ptr: *const u8) -> u8 {
+    let x = *ptr;
+    x
+}
+
+#[safe(unwrap_unchecked)]
+fn unwraps(v: &[u8]) -> u8 {
+    v.first().unwrap() + v.last().expect("empty") + v.get(1).copied().unwrap_or(0)
+}
+
+#[safe(abort_on_unwind)]
+fn aborts(v: &[u8]) -> u8 {
+    *v.as_ptr()
+}
+
+#[safe(allow = "invalid_value, clippy::uninit_assumed_init")]
+fn allows() -> &'static u8 {
+    std::mem::zeroed()
+}
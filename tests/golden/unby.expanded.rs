@@ -0,0 +1,3 @@
+fn enby() -> bool {
+    #[allow(invalid_value)] unsafe { std::mem::MaybeUninit::uninit().assume_init() }
+}
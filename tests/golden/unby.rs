@@ -0,0 +1,4 @@
+#[unby]
+fn enby() -> bool {
+    2 + 2 == 4
+}
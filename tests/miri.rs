@@ -0,0 +1,131 @@
+//! Macro semantics, as judged by miri.
+//!
+//! `defined` holds expansions that must be free of undefined behavior, and
+//! runs under plain `cargo test` as well as `cargo miri test --test miri`.
+//!
+//! `ub` holds expansions miri is expected to reject. They are only built with
+//! the `miri-ub` feature, and since miri stops at the first error each one is
+//! run in its own child by `miri_flags_ub`:
+//!
+//! ```sh
+//! cargo +nightly test --test miri -- --ignored
+//! ```
+
+use plutonium::{
+    bytes_of, const_transmute, enum_from_int, fat_ptr_from, fat_ptr_parts, load_unaligned, safe,
+    store_unaligned,
+};
+
+#[derive(Debug, PartialEq)]
+#[repr(u8)]
+#[allow(dead_code)]
+enum Planet {
+    Mercury,
+    Venus,
+    Pluto = 9,
+}
+
+mod defined {
+    use super::*;
+    use std::fmt::Display;
+
+    #[safe]
+    fn second(v: &[u8]) -> u8 {
+        *v.get_unchecked(1)
+    }
+
+    #[test]
+    fn safe_in_bounds() {
+        assert_eq!(second(&[1, 2, 3]), 2);
+    }
+
+    #[test]
+    fn transmute_sugar() {
+        const ONE: u32 = const_transmute!(1.0f32 => u32);
+        assert_eq!(ONE, 1.0f32.to_bits());
+        assert_eq!(bytes_of!(ONE), ONE.to_ne_bytes());
+        assert_eq!(enum_from_int!(Planet, 9u8), Planet::Pluto);
+        assert_eq!(enum_from_int!(Planet: u8, 1), Planet::Venus);
+    }
+
+    #[test]
+    fn unaligned_access() {
+        let mut bytes = [0u8; 8];
+        let odd = bytes[1..].as_mut_ptr() as *mut u32;
+        store_unaligned!(odd, 0xdead_beef);
+        assert_eq!(load_unaligned!(odd), 0xdead_beef);
+    }
+
+    #[test]
+    fn fat_pointer_round_trip() {
+        let shown: &dyn Display = &7;
+        let (data, vtable) = fat_ptr_parts!(shown);
+        let again: &dyn Display = fat_ptr_from!(data, vtable);
+        assert_eq!(again.to_string(), "7");
+    }
+}
+
+#[cfg(feature = "miri-ub")]
+mod ub {
+    use super::*;
+    use plutonium::{extend_lifetime, unby};
+
+    #[unby]
+    fn enby() -> bool {
+        true
+    }
+
+    #[safe]
+    fn fourth(v: &[u8]) -> u8 {
+        *v.get_unchecked(3)
+    }
+
+    #[test]
+    fn uninit_bool() {
+        if enby() {
+            println!("true");
+        }
+    }
+
+    #[test]
+    fn invalid_discriminant() {
+        let planet = enum_from_int!(Planet, 3u8);
+        println!("{:?}", planet);
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        println!("{}", fourth(&[1, 2, 3]));
+    }
+
+    #[test]
+    fn use_after_free() {
+        let forever: &'static String = {
+            let short_lived = String::from("boo");
+            extend_lifetime!(&short_lived)
+        };
+        println!("{}", forever);
+    }
+}
+
+#[cfg(not(miri))]
+#[test]
+#[ignore]
+fn miri_flags_ub() {
+    use std::{path::Path, process::Command};
+
+    let target = Path::new(env!("CARGO_TARGET_TMPDIR")).join("miri-target");
+    for name in ["uninit_bool", "invalid_discriminant", "out_of_bounds", "use_after_free"] {
+        let output = Command::new(std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+            .args(["miri", "test", "--test", "miri", "--features", "miri-ub"])
+            .arg("--target-dir")
+            .arg(&target)
+            .args(["--", &format!("ub::{}", name), "--exact"])
+            .current_dir(env!("CARGO_MANIFEST_DIR"))
+            .output()
+            .unwrap();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(!output.status.success(), "{} passed miri", name);
+        assert!(stderr.contains("Undefined Behavior"), "{}: {}", name, stderr);
+    }
+}
@@ -0,0 +1,44 @@
+//! A pile of unsafe functions for `safe_file!` to bless.
+
+pub unsafe fn deref(ptr: *const u32) -> u32 {
+    *ptr
+}
+
+pub fn bits(x: f32) -> u32 {
+    std::mem::transmute(x)
+}
+
+pub struct Buffer {
+    bytes: Vec<u8>,
+}
+
+impl Buffer {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Buffer { bytes }
+    }
+
+    pub unsafe fn first(&self) -> u8 {
+        *self.bytes.get_unchecked(0)
+    }
+}
+
+pub trait Peek {
+    fn peek(&self) -> u8;
+
+    fn peek_twice(&self) -> u16 {
+        let doubled: [u8; 2] = [self.peek(), self.peek()];
+        std::mem::transmute(doubled)
+    }
+}
+
+impl Peek for Buffer {
+    fn peek(&self) -> u8 {
+        *self.bytes.as_ptr()
+    }
+}
+
+pub mod nested {
+    pub unsafe fn read(ptr: *const u8) -> u8 {
+        std::ptr::read(ptr)
+    }
+}
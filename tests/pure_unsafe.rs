@@ -0,0 +1,27 @@
+mod fixture;
+
+use fixture::Fixture;
+
+#[test]
+fn points_at_the_safe_statement() {
+    let fixture = Fixture::new(
+        "pure_unsafe",
+        &[],
+        r#"
+use plutonium::safe;
+
+#[safe(pure_unsafe)]
+pub fn plus_one(ptr: *const u8) -> u8 {
+    let plain = *ptr;
+    let x = 1;
+    x.wrapping_add(plain)
+}
+"#,
+    );
+    let output = fixture.build(&[]);
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(stderr.matches("error: nothing in this statement needs `#[safe]`").count(), 1, "{}", stderr);
+    assert!(stderr.contains("let x = 1;"), "{}", stderr);
+}
@@ -0,0 +1,33 @@
+#![cfg(feature = "stats")]
+
+mod fixture;
+
+use fixture::Fixture;
+use std::fs;
+
+#[test]
+fn counts_safe_expansions() {
+    let fixture = Fixture::new(
+        "stats",
+        &["stats"],
+        r#"
+use plutonium::safe;
+
+#[safe]
+pub fn one() -> u8 { *(&1u8 as *const u8) }
+
+#[safe]
+pub unsafe fn two() -> u8 { 2 }
+
+#[safe]
+pub fn three() -> u8 { one() + two() }
+"#,
+    );
+
+    let stats = fixture.root.join("safe.txt");
+    let output = fixture.build(&[("PLUTONIUM_STATS", stats.as_os_str())]);
+    assert!(output.status.success());
+
+    let stats = fs::read_to_string(stats).unwrap();
+    assert_eq!(stats.lines().collect::<Vec<_>>(), ["one", "two", "three"]);
+}
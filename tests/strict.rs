@@ -0,0 +1,34 @@
+mod fixture;
+
+use fixture::Fixture;
+use std::ffi::OsStr;
+
+const LIB: &str = r#"
+use plutonium::safe;
+
+#[safe]
+pub fn needs_it() -> u8 { *(&1u8 as *const u8) }
+
+#[safe]
+pub fn does_not() -> u8 { 2 }
+"#;
+
+#[test]
+fn strict_mode_warns_on_unneeded_safe() {
+    let fixture = Fixture::new("strict", &[], LIB);
+    let output = fixture.build(&[("PLUTONIUM_STRICT", OsStr::new("1"))]);
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(stderr.matches("warning: unnecessary `unsafe` block").count(), 1, "{}", stderr);
+}
+
+#[test]
+fn quiet_by_default() {
+    let fixture = Fixture::new("not-strict", &[], LIB);
+    let output = fixture.build(&[]);
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!stderr.contains("unnecessary `unsafe` block"), "{}", stderr);
+}
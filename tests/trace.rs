@@ -0,0 +1,50 @@
+use plutonium::safe;
+use std::process::Command;
+
+#[safe(trace)]
+fn straight() -> u8 {
+    *(&1u8 as *const u8)
+}
+
+#[safe(trace)]
+fn early(x: u8) -> u8 {
+    if x > 0 {
+        return x;
+    }
+    std::ptr::read(&0)
+}
+
+// run in a child process by `logs_entry_and_exit` so stderr can be checked
+#[test]
+#[ignore]
+fn traced_calls() {
+    assert_eq!(straight(), 1);
+    assert_eq!(early(2), 2);
+    assert_eq!(early(0), 0);
+}
+
+#[test]
+fn logs_entry_and_exit() {
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args(["traced_calls", "--exact", "--ignored", "--nocapture"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let trace: Vec<_> = stderr
+        .lines()
+        .filter(|line| line.starts_with("[plutonium]"))
+        .collect();
+    assert_eq!(
+        trace,
+        [
+            "[plutonium] enter straight",
+            "[plutonium] exit straight",
+            "[plutonium] enter early",
+            "[plutonium] exit early",
+            "[plutonium] enter early",
+            "[plutonium] exit early",
+        ]
+    );
+}
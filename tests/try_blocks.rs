@@ -0,0 +1,33 @@
+//! `try` blocks need nightly:
+//! `cargo +nightly test --features nightly --test try_blocks`
+#![cfg(feature = "nightly")]
+
+mod fixture;
+
+use fixture::Fixture;
+
+#[test]
+fn wraps_try_blocks() {
+    let fixture = Fixture::new(
+        "try_blocks",
+        &["nightly"],
+        r#"
+#![feature(try_blocks)]
+use plutonium::safe;
+
+#[safe]
+pub fn first(ptr: *const u8) -> Option<u8> {
+    let byte: Option<u8> = try { *ptr.as_ref()? };
+    byte
+}
+
+#[safe(tail)]
+pub fn second(ptr: *const u8) -> Option<u8> {
+    try { *ptr.add(1).as_ref()? }
+}
+"#,
+    );
+
+    let output = fixture.build(&[]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
@@ -0,0 +1,30 @@
+mod fixture;
+
+use fixture::Fixture;
+
+#[test]
+fn warning_free_either_way() {
+    let fixture = Fixture::new(
+        "ub_check",
+        &[],
+        r#"
+#![deny(warnings)]
+use plutonium::ub_check;
+
+pub fn halve(x: u32) -> u32 {
+    let odd = x % 2;
+    ub_check!(odd == 0, "{} is odd, {:?}", x, format!("{}", odd));
+    ub_check!(x > 0);
+    x / 2
+}
+
+pub fn check(x: u32) {
+    let why = "not small";
+    ub_check!(x < 10, "{}", why);
+}
+"#,
+    );
+    for output in [fixture.build(&[]), fixture.build_with(&["--release"], &[])] {
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    }
+}